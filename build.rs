@@ -0,0 +1,87 @@
+//! Generates a regression test for each part of each input under `data/N`,
+//! included by `tests/regression.rs`. Each runs only if its day's feature
+//! is enabled, and `regression_cases` is set when any of them will.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_else(|_| vec![]);
+    paths.sort();
+    paths
+}
+
+/// The sidecar holding the expected answers for `input`, if it has one
+fn sidecar(input: &Path) -> Option<toml::Table> {
+    let path = input.with_extension("answers.toml");
+    let text = fs::read_to_string(&path).ok()?;
+    match text.parse::<toml::Table>() {
+        Ok(table) => Some(table),
+        Err(e) => panic!("{}: {e}", path.display()),
+    }
+}
+
+fn main() {
+    let data = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("data");
+    println!("cargo:rerun-if-changed={}", data.display());
+    println!("cargo::rustc-check-cfg=cfg(regression_cases)");
+
+    let mut tests = String::new();
+    let mut enabled = false;
+    for dir in sorted_entries(&data) {
+        let Some(day) = dir
+            .file_name()
+            .and_then(|name| name.to_str()?.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        for input in sorted_entries(&dir) {
+            let Some(file) = input.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some(stem) = file.strip_suffix(".txt") else {
+                continue;
+            };
+            let ident = stem
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect::<String>();
+            let Some(expected) = sidecar(&input) else {
+                continue;
+            };
+            let slow = expected.get("slow").and_then(|v| v.as_bool());
+            let ignore = if slow == Some(true) {
+                "#[ignore = \"slow; run with --ignored\"]\n"
+            } else {
+                ""
+            };
+            for part in [1, 2]
+                .into_iter()
+                .filter(|p| expected.contains_key(&format!("part{p}")))
+            {
+                enabled |= env::var_os(format!("CARGO_FEATURE_DAY{day}")).is_some();
+                writeln!(
+                    tests,
+                    "#[test]\n#[cfg(feature = \"day{day}\")]\n{ignore}fn day{day}_{ident}_part{part}() {{\n    \
+                     check({day}, {file:?}, {part});\n}}\n"
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    if enabled {
+        println!("cargo::rustc-cfg=regression_cases");
+    }
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("regression.rs");
+    fs::write(out, tests).unwrap();
+}
@@ -0,0 +1,183 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The result of solving one part of a puzzle.
+///
+/// Answers compare by their rendered form, so `Integer(24)` equals
+/// `Text("24")` and a `Grid` equals the `Text` of its rows joined by newlines.
+/// Comparing with a bare `i128` is the same as comparing with `Integer`.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    Grid(Vec<String>),
+}
+
+impl Answer {
+    pub fn grid<I, S>(rows: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Answer::Grid(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// The numeric value of this answer, if it has one
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(n) => Some(*n),
+            Answer::Text(s) => s.trim().parse().ok(),
+            Answer::Grid(_) => None,
+        }
+    }
+
+    /// Name of the variant, for reports that record the answer's type
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "text",
+            Answer::Grid(_) => "grid",
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::Text(s) => f.write_str(s),
+            Answer::Grid(rows) => f.write_str(&rows.join("\n")),
+        }
+    }
+}
+
+/// Serialized with its type alongside, as `{"type": "integer", "value": 24000}`.
+/// A grid's value is its list of rows.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut answer = serializer.serialize_struct("Answer", 2)?;
+        answer.serialize_field("type", self.kind())?;
+        match self {
+            Answer::Integer(n) => answer.serialize_field("value", n)?,
+            Answer::Text(s) => answer.serialize_field("value", s)?,
+            Answer::Grid(rows) => answer.serialize_field("value", rows)?,
+        }
+        answer.end()
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::Integer(a), Answer::Integer(b)) => a == b,
+            (Answer::Grid(a), Answer::Grid(b)) => a == b,
+            _ => self.to_string() == other.to_string(),
+        }
+    }
+}
+
+impl Eq for Answer {}
+
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Answer::Text(s) => s == other,
+            _ => self.to_string().as_str() == other,
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<i128> for Answer {
+    fn eq(&self, other: &i128) -> bool {
+        *self == Answer::Integer(*other)
+    }
+}
+
+macro_rules! impl_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Integer(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_integer!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl From<u128> for Answer {
+    fn from(n: u128) -> Self {
+        match i128::try_from(n) {
+            Ok(n) => Answer::Integer(n),
+            Err(_) => Answer::Text(n.to_string()),
+        }
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_compares_integers_with_their_text() {
+        assert_eq!(Answer::from(24000), Answer::from("24000"));
+        assert_eq!(Answer::from(24000), "24000");
+        assert_eq!(Answer::from("24000"), 24000);
+        assert_ne!(Answer::from(24000), Answer::from("24001"));
+    }
+
+    #[test]
+    fn it_compares_with_integers_as_with_integer_answers() {
+        for text in ["24", " 24", "24\n", "024", "+24"] {
+            let answer = Answer::from(text);
+            assert_eq!(answer == 24, answer == Answer::from(24), "{text:?}");
+        }
+        assert_ne!(Answer::from(" 24"), 24);
+        assert_eq!(Answer::from(" 24").as_integer(), Some(24));
+    }
+
+    #[test]
+    fn it_serializes_with_its_type() -> Result<(), serde_json::Error> {
+        assert_eq!(
+            serde_json::to_string(&Answer::from(13_743_542_639_657_u64))?,
+            r#"{"type":"integer","value":13743542639657}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Answer::from("CMZ"))?,
+            r#"{"type":"text","value":"CMZ"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Answer::grid(["#.", ".#"]))?,
+            r##"{"type":"grid","value":["#.",".#"]}"##
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn it_compares_grids_with_their_rows() {
+        let grid = Answer::grid(["#..", ".#."]);
+        assert_eq!(grid.to_string(), "#..\n.#.");
+        assert_eq!(grid, Answer::from("#..\n.#."));
+        assert_eq!(grid.as_integer(), None);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::AocError;
+use crate::registry::Registry;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part(u8),
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Stage::Parse => f.write_str("parse"),
+            Stage::Part(p) => write!(f, "part{p}"),
+        }
+    }
+}
+
+pub struct Timing {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Timing {
+    /// Summarise a non-empty set of samples
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Timing {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+pub struct Measurement {
+    pub day: usize,
+    pub input: String,
+    pub stage: Stage,
+    pub timing: Timing,
+}
+
+impl Measurement {
+    /// Key identifying this measurement in a saved baseline
+    pub fn key(&self) -> String {
+        format!("day{} {} {}", self.day, self.input, self.stage)
+    }
+}
+
+fn sample<T>(runs: usize, mut f: impl FnMut() -> Result<T, AocError>) -> Result<Timing, AocError> {
+    let mut samples = Vec::with_capacity(runs);
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        f()?;
+        samples.push(start.elapsed());
+    }
+    Ok(Timing::from_samples(samples))
+}
+
+/// Time reading and parsing `input` for `day`, then solving each of `parts`
+/// from the parsed input, `runs` times each
+pub fn bench_day(
+    registry: &Registry,
+    day: usize,
+    input: &str,
+    parts: &[u8],
+    runs: usize,
+) -> Result<Vec<Measurement>, AocError> {
+    let load = || {
+        registry
+            .solver(day, input)
+            .ok_or_else(|| AocError::unsolvable("Day is not registered").with_day(day))
+    };
+    let measure = |stage: Stage, timing: Timing| Measurement {
+        day,
+        input: input.to_string(),
+        stage,
+        timing,
+    };
+
+    let parse = sample(runs, || load()?.parse())?;
+    let mut measurements = vec![measure(Stage::Parse, parse)];
+    let solver = load()?;
+    solver.parse()?;
+    for &part in parts {
+        let timing = sample(runs, || solver.answer(part))?;
+        measurements.push(measure(Stage::Part(part), timing));
+    }
+    Ok(measurements)
+}
+
+/// Median timings from an earlier run, keyed by `Measurement::key`, in
+/// nanoseconds.
+#[derive(Default)]
+pub struct Baseline {
+    medians: BTreeMap<String, u64>,
+}
+
+impl Baseline {
+    /// Load a saved baseline. A missing file is an empty baseline.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Baseline::default()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Baseline {
+            medians: toml::from_str(&text)?,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(&self.medians)?)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, measurement: &Measurement) {
+        self.medians.insert(
+            measurement.key(),
+            measurement.timing.median.as_nanos() as u64,
+        );
+    }
+
+    pub fn median(&self, measurement: &Measurement) -> Option<Duration> {
+        self.medians
+            .get(&measurement.key())
+            .map(|&nanos| Duration::from_nanos(nanos))
+    }
+
+    /// How much slower `measurement` is than the baseline, as a fraction,
+    /// when that exceeds `threshold`.
+    pub fn regression(&self, measurement: &Measurement, threshold: f64) -> Option<f64> {
+        let baseline = self.median(measurement)?.as_secs_f64();
+        let change = measurement.timing.median.as_secs_f64() / baseline - 1.0;
+        (baseline > 0.0 && change > threshold).then_some(change)
+    }
+}
+
+/// Format a duration with three significant decimals and a readable unit
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos() as f64;
+    if nanos < 1e3 {
+        format!("{nanos:.0}ns")
+    } else if nanos < 1e6 {
+        format!("{:.3}µs", nanos / 1e3)
+    } else if nanos < 1e9 {
+        format!("{:.3}ms", nanos / 1e6)
+    } else {
+        format!("{:.3}s", nanos / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn measurement(median_ms: u64) -> Measurement {
+        Measurement {
+            day: 7,
+            input: "test1.txt".to_string(),
+            stage: Stage::Part(1),
+            timing: Timing::from_samples(vec![Duration::from_millis(median_ms)]),
+        }
+    }
+
+    #[test]
+    fn it_summarises_samples() {
+        let timing =
+            Timing::from_samples([5, 1, 3].into_iter().map(Duration::from_millis).collect());
+        assert_eq!(timing.min, Duration::from_millis(1));
+        assert_eq!(timing.median, Duration::from_millis(3));
+        assert_eq!(timing.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn it_flags_regressions_against_the_baseline() {
+        let mut baseline = Baseline::default();
+        baseline.record(&measurement(100));
+        assert_eq!(baseline.regression(&measurement(105), 0.1), None);
+        assert!(baseline.regression(&measurement(150), 0.1).is_some());
+        assert_eq!(Baseline::default().regression(&measurement(150), 0.1), None);
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn it_times_every_stage() -> Result<(), AocError> {
+        let measurements = bench_day(&Registry::default(), 1, "test1.txt", &[1, 2], 3)?;
+        let stages = measurements.iter().map(|m| m.stage).collect::<Vec<Stage>>();
+        assert!(stages == [Stage::Parse, Stage::Part(1), Stage::Part(2)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use adventofcode2022::logging::{self, Filter, LOG_VAR};
+use adventofcode2022::registry::Registry;
+use adventofcode2022::server;
+use clap::{ArgAction, Parser};
+use std::env;
+use std::error::Error;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+#[derive(Parser)]
+#[command(
+    name = "aoc-server",
+    about = "Answer puzzles posted to http://localhost:PORT/day/N/part/P as JSON"
+)]
+struct Cli {
+    /// Port to listen on, on localhost only
+    #[arg(long, short, default_value_t = 8022)]
+    port: u16,
+    /// Log diagnostics to stderr: -v for info, -vv for debug, -vvv for
+    /// trace. $AOC_LOG sets levels per day, as in `warn,day13=trace`
+    #[arg(long, short, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+fn execute(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let filter = match env::var(LOG_VAR) {
+        Ok(spec) => Filter::from_str(&spec).map_err(|e| format!("{LOG_VAR}: {e}"))?,
+        Err(_) => Filter::quiet(),
+    };
+    logging::init(filter.with_verbosity(cli.verbose))?;
+    let listener = server::bind(cli.port)?;
+    eprintln!("Listening on http://{}", listener.local_addr()?);
+    server::serve(&listener, &Registry::default());
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match execute(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
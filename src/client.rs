@@ -0,0 +1,248 @@
+use crate::solvable::data_root;
+use serde::Deserialize;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use ureq::Agent;
+
+/// Environment variable holding the session cookie of a logged in user
+pub const SESSION_VAR: &str = "AOC_SESSION";
+/// Environment variable overriding the site to talk to, such as a local stub
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u32 = 2022;
+
+/// Keep this out of version control: it holds the session token.
+///
+/// ```toml
+/// session = "53616c7465645f5f..."
+/// base_url = "http://localhost:8080"
+/// ```
+pub fn config_path() -> PathBuf {
+    data_root().join("aoc.toml")
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub session: Option<String>,
+    pub base_url: Option<String>,
+}
+
+impl Config {
+    /// Read `path`, or the defaults if there is no such file
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        match fs::read_to_string(path) {
+            Ok(text) => {
+                toml::from_str(&text).map_err(|e| format!("{}: {e}", path.display()).into())
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(e) => Err(format!("{}: {e}", path.display()).into()),
+        }
+    }
+}
+
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    agent: Agent,
+}
+
+impl Client {
+    pub fn new(base_url: impl Into<String>, session: Option<String>) -> Self {
+        let agent = ureq::builder()
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build();
+        Client {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session,
+            agent,
+        }
+    }
+
+    /// A client for `$AOC_BASE_URL` with `$AOC_SESSION`, falling back on
+    /// `config`, then the real site
+    pub fn from_env(config: Config) -> Self {
+        let var = |name: &str| env::var(name).ok().filter(|v| !v.is_empty());
+        Client::new(
+            var(BASE_URL_VAR)
+                .or(config.base_url)
+                .unwrap_or_else(|| DEFAULT_BASE_URL.to_string()),
+            var(SESSION_VAR).or(config.session),
+        )
+    }
+
+    fn url(&self, day: usize, path: &str) -> String {
+        format!("{}/{YEAR}/day/{day}{path}", self.base_url)
+    }
+
+    fn cookie(&self) -> Result<String, Box<dyn Error>> {
+        match &self.session {
+            Some(session) => Ok(format!("session={session}")),
+            None => Err(format!(
+                "No session token; set ${SESSION_VAR} or `session` in {}",
+                config_path().display()
+            )
+            .into()),
+        }
+    }
+
+    /// The puzzle input for `day`
+    pub fn input(&self, day: usize) -> Result<String, Box<dyn Error>> {
+        let url = self.url(day, "/input");
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie()?)
+            .call()
+            .map_err(|e| request_error(&url, e))?;
+        Ok(response.into_string()?)
+    }
+
+    /// Post `answer` to `part` of `day`, returning the page the site
+    /// responds with
+    pub fn answer(&self, day: usize, part: u8, answer: &str) -> Result<String, Box<dyn Error>> {
+        let url = self.url(day, "/answer");
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie()?)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| request_error(&url, e))?;
+        Ok(response.into_string()?)
+    }
+}
+
+fn request_error(url: &str, error: ureq::Error) -> Box<dyn Error> {
+    match error {
+        ureq::Error::Status(code @ (400 | 500), _) => {
+            format!("{url}: HTTP {code}; is the session token valid?").into()
+        }
+        ureq::Error::Status(404, _) => format!("{url}: not found; is the puzzle unlocked?").into(),
+        ureq::Error::Status(code, _) => format!("{url}: HTTP {code}").into(),
+        e => format!("{url}: {e}").into(),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+/// Download the input for `day` to `path`, unless it is already there
+pub fn fetch(client: &Client, day: usize, path: &Path) -> Result<Fetched, Box<dyn Error>> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+    let input = client.input(day)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, input)?;
+    Ok(Fetched::Downloaded)
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Answer one request with each of `responses` on a local port. Returns
+    /// the base URL, and a handle giving back each request as received.
+    pub(crate) fn stub(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let mut requests = vec![];
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                requests.push(request);
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Stub\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+            requests
+        });
+        (base_url, server)
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-client-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn it_downloads_and_caches_the_input() -> Result<(), Box<dyn Error>> {
+        let (base_url, server) = stub(vec![(200, "1\n2\n")]);
+        let client = Client::new(base_url + "/", Some("abc".to_string()));
+        let path = scratch("download").join("1").join("input.txt");
+
+        assert_eq!(fetch(&client, 1, &path)?, Fetched::Downloaded);
+        assert_eq!(fs::read_to_string(&path)?, "1\n2\n");
+        // The stub has stopped serving, so this must not make a request
+        assert_eq!(fetch(&client, 1, &path)?, Fetched::Cached);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2022/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("session=abc"));
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_a_rejected_session() {
+        let (base_url, server) = stub(vec![(400, "Puzzle inputs differ by user.")]);
+        let client = Client::new(base_url, Some("stale".to_string()));
+        let path = scratch("rejected").join("input.txt");
+
+        let error = fetch(&client, 3, &path).unwrap_err();
+        assert!(error.to_string().contains("session token"), "{error}");
+        assert!(!path.exists());
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn it_needs_a_session_to_download() {
+        let client = Client::new("http://127.0.0.1:9", None);
+        let error = client.input(1).unwrap_err();
+        assert!(error.to_string().contains(SESSION_VAR));
+    }
+
+    #[test]
+    fn it_reads_a_missing_config_as_empty() -> Result<(), Box<dyn Error>> {
+        let config = Config::load(&scratch("config").join("aoc.toml"))?;
+        assert!(config.session.is_none() && config.base_url.is_none());
+        Ok(())
+    }
+}
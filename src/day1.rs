@@ -1,68 +1,66 @@
-use crate::solvable::Solvable;
-use std::fs;
-use std::{error::Error, path::PathBuf};
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::parse::groups;
+use crate::solvable::{Solvable, Source};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-impl Solution {
-    fn get_elves(&self) -> Result<Vec<u32>, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut elves: Vec<u32> = Vec::new();
-        let mut elf: u32 = 0;
-        for line in input.lines() {
-            if line == "" {
-                elves.push(elf);
-                elf = 0;
-            } else {
-                elf += line.parse::<u32>()?;
-            }
-        }
+fn parse_elves(input: &str) -> Result<Vec<u32>, AocError> {
+    groups(input)
+        .iter()
+        .map(|elf| {
+            elf.iter()
+                .map(|line| line.value::<u32>(line.text, "calories"))
+                .sum()
+        })
+        .collect()
+}
 
-        // At end of file, push the final elf
-        elves.push(elf);
+impl Solvable<1> for Solution {
+    type Input = Vec<u32>;
 
-        Ok(elves)
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
-}
 
-impl Solvable<1> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Vec<u32>, AocError> {
+        parse_elves(text)
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let max = self.get_elves()?.into_iter().max().ok_or("Empty input")?;
+    fn part1(&self, elves: &Vec<u32>, _: &Params) -> Result<Answer, AocError> {
+        let max = elves
+            .iter()
+            .max()
+            .ok_or_else(|| AocError::unsolvable("Empty input"))?;
 
-        Ok(format!("{}", max))
+        Ok((*max).into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let mut elves = self.get_elves()?;
-        elves.sort_by(|a, b| b.partial_cmp(a).unwrap());
-        let sum = elves[..3].into_iter().sum::<u32>();
-        Ok(sum.to_string())
+    fn part2(&self, elves: &Vec<u32>, _: &Params) -> Result<Answer, AocError> {
+        let mut elves = elves.clone();
+        elves.sort_by(|a, b| b.cmp(a));
+        let sum = elves.iter().take(3).sum::<u32>();
+        Ok(sum.into())
     }
 }
 
 #[allow(unused_imports)]
 mod test {
     use super::*;
+    use std::error::Error;
 
     #[test]
-    fn gives_the_right_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "24000");
-        Ok(())
-    }
-
-    #[test]
-    fn gives_the_right_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "45000");
+    fn gives_the_right_answers_for_inline_text() -> Result<(), Box<dyn Error>> {
+        let solution = Solution::from_text("100\n200\n\n250\n\n50\n");
+        assert_eq!(solution.answer1()?, "300");
+        assert_eq!(solution.answer2()?, "600");
         Ok(())
     }
 }
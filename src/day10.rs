@@ -1,9 +1,28 @@
-use crate::solvable::Solvable;
-use std::fs;
-use std::{error::Error, path::PathBuf};
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::parse::parse_lines;
+use crate::solvable::{Simulation, Solvable, Source};
+use std::fmt::{Display, Formatter, Result as FmtResult};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
+}
+
+pub enum Instruction {
+    Noop,
+    Addx(i32),
+}
+
+fn parse_program(input: &str) -> Result<Vec<Instruction>, AocError> {
+    parse_lines(input, |line| match line.text {
+        "noop" => Ok(Instruction::Noop),
+        _ if line.text.starts_with("addx ") => {
+            let [x] = line.template("addx {}")?;
+            Ok(Instruction::Addx(line.value(x, "addx operand")?))
+        }
+        _ => Err(line.error(format!("Unrecognized command \"{}\"", line.text))),
+    })
 }
 
 fn relevant_cycle(cycle: i32) -> bool {
@@ -13,102 +32,123 @@ fn relevant_cycle(cycle: i32) -> bool {
 fn draw_pixel(screen_buffer: &mut [char], pixel: i32, sprite_pos: i32) {
     let p = pixel % 40;
     if sprite_pos - 1 <= p && p <= sprite_pos + 1 {
-        screen_buffer[pixel as usize] = '#';
+        if let Some(cell) = screen_buffer.get_mut(pixel as usize) {
+            *cell = '#';
+        }
     }
 }
 
-impl Solvable<10> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
+/// The CPU running the program a clock cycle at a time, drawing on the
+/// screen as it goes
+pub struct Cpu<'a> {
+    program: &'a [Instruction],
+    next: usize,
+    /// Whether the current addx has already used its first cycle
+    adding: bool,
+    /// The cycle about to start, counting from 1
+    cycle: i32,
+    x: i32,
+    signal_strengths: i32,
+    screen: [char; 240],
+}
+
+impl<'a> Cpu<'a> {
+    fn new(program: &'a [Instruction]) -> Self {
+        Cpu {
+            program,
+            next: 0,
+            adding: false,
+            cycle: 1,
+            x: 1,
+            signal_strengths: 0,
+            screen: ['.'; 240],
         }
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut register = 1_i32;
-        let mut cycle = 1_i32;
-        let mut signal_strengths = vec![];
-        for line in input.lines() {
-            if relevant_cycle(cycle) {
-                signal_strengths.push(cycle * register);
-            }
-            let instruction = &line[0..4];
-            match instruction {
-                "noop" => cycle += 1,
-                "addx" => {
-                    cycle += 1;
-                    if relevant_cycle(cycle) {
-                        signal_strengths.push(cycle * register);
-                    }
-                    cycle += 1;
-                    let x = line[5..].parse::<i32>()?;
-                    register += x;
-                }
-                _ => panic!("Unrecognized command {instruction}"),
-            }
-        }
-        Ok(signal_strengths.iter().sum::<i32>().to_string())
+    fn run(mut self) -> Self {
+        while self.step().is_ok_and(|ticked| ticked) {}
+        self
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut screen_buffer = ['.'; 240];
-        let mut sprite_pos = 1_i32;
-        let mut cycle = 1;
-        for line in input.lines() {
-            let instruction = &line[0..4];
-            draw_pixel(&mut screen_buffer, cycle - 1, sprite_pos);
-            match instruction {
-                "noop" => {
-                    cycle += 1;
-                }
-                "addx" => {
-                    cycle += 1;
-                    draw_pixel(&mut screen_buffer, cycle - 1, sprite_pos);
-                    cycle += 1;
-                    let x = line[5..].parse::<i32>()?;
-                    sprite_pos += x;
-                }
-                _ => panic!("Unrecognized command {instruction}"),
+    /// Each row of the screen is 40 pixels wide
+    fn rows(&self) -> impl Iterator<Item = String> + '_ {
+        self.screen.chunks(40).map(|row| row.iter().collect())
+    }
+}
+
+impl Simulation for Cpu<'_> {
+    fn step(&mut self) -> Result<bool, AocError> {
+        let Some(instruction) = self.program.get(self.next) else {
+            return Ok(false);
+        };
+        // During the cycle
+        if relevant_cycle(self.cycle) {
+            self.signal_strengths += self.cycle * self.x;
+        }
+        draw_pixel(&mut self.screen, self.cycle - 1, self.x);
+        // At its end
+        self.cycle += 1;
+        match instruction {
+            Instruction::Noop => self.next += 1,
+            Instruction::Addx(_) if !self.adding => self.adding = true,
+            Instruction::Addx(v) => {
+                self.x += v;
+                self.adding = false;
+                self.next += 1;
             }
         }
+        Ok(true)
+    }
+}
 
-        // Write the final screen state to an output buffer
-        let mut output_buffer = String::new();
-        for (i, c) in screen_buffer.iter().enumerate() {
-            if i > 0 && i % 40 == 0 {
-                output_buffer.push('\n');
-            }
-            output_buffer.push(*c);
+impl Display for Cpu<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(
+            f,
+            "Cycle {}: X = {}, signal strengths so far {}",
+            self.cycle, self.x, self.signal_strengths
+        )?;
+        for row in self.rows() {
+            writeln!(f, "{row}")?;
+        }
+        match self.program.get(self.next) {
+            Some(Instruction::Noop) => write!(f, "Next: noop"),
+            Some(Instruction::Addx(v)) if self.adding => write!(f, "Finishing: addx {v}"),
+            Some(Instruction::Addx(v)) => write!(f, "Next: addx {v}"),
+            None => write!(f, "The program has finished"),
         }
-        Ok(output_buffer)
     }
 }
 
-#[allow(unused_imports)]
-mod test {
-    use super::*;
+impl Solvable<10> for Solution {
+    type Input = Vec<Instruction>;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
+    }
+
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Vec<Instruction>, AocError> {
+        parse_program(text)
+    }
+
+    fn part1(&self, program: &Vec<Instruction>, _: &Params) -> Result<Answer, AocError> {
+        Ok(Cpu::new(program).run().signal_strengths.into())
+    }
 
-    #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "13140");
-        Ok(())
+    fn part2(&self, program: &Vec<Instruction>, _: &Params) -> Result<Answer, AocError> {
+        Ok(Answer::grid(Cpu::new(program).run().rows()))
     }
 
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(
-            solution.answer2()?,
-            "##..##..##..##..##..##..##..##..##..##..\n\
-             ###...###...###...###...###...###...###.\n\
-             ####....####....####....####....####....\n\
-             #####.....#####.....#####.....#####.....\n\
-             ######......######......######......####\n\
-             #######.......#######.......#######....."
-        );
-        Ok(())
+    fn simulation<'a>(
+        &self,
+        program: &'a Self::Input,
+        _: &'a Params,
+        _: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        Ok(Some(Box::new(Cpu::new(program))))
     }
 }
@@ -1,29 +1,47 @@
-#[allow(dead_code)]
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::{Param, Params};
+use crate::solvable::{Simulation, Solvable, Source};
+use log::trace;
 use std::collections::VecDeque;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::mem;
-use std::{error::Error, path::PathBuf};
 
-#[allow(dead_code)]
-pub struct Solution<'a> {
-    filepath: PathBuf,
-    starting_state: Vec<Monkey<'a>>,
+pub struct Solution {
+    source: Source,
+}
+
+#[derive(Clone, Copy)]
+enum Operation {
+    Add(u128),
+    Multiply(u128),
+    Square,
+}
+
+impl Operation {
+    fn apply(&self, x: u128) -> u128 {
+        match self {
+            Operation::Add(y) => x + y,
+            Operation::Multiply(y) => x * y,
+            Operation::Square => x * x,
+        }
+    }
 }
 
 #[derive(Clone)]
-struct Monkey<'a> {
+pub struct Monkey {
     items: VecDeque<u128>,
-    operation: &'a dyn Fn(u128) -> u128,
+    operation: Operation,
     divisor: u128,
     true_target: usize,
     false_target: usize,
     num_inspections: u128,
 }
 
-impl<'a> Monkey<'a> {
+impl Monkey {
     fn new(
         items: Vec<u128>,
-        operation: &'a dyn Fn(u128) -> u128,
+        operation: Operation,
         divisor: u128,
         true_target: usize,
         false_target: usize,
@@ -39,123 +57,232 @@ impl<'a> Monkey<'a> {
     }
 }
 
-fn setup_test1<'a>() -> Vec<Monkey<'a>> {
-    vec![
-        Monkey::new(vec![79, 98], &|x| x * 19, 23, 2, 3),
-        Monkey::new(vec![54, 65, 75, 74], &|x| x + 6, 19, 2, 0),
-        Monkey::new(vec![79, 60, 97], &|x| x * x, 13, 1, 3),
-        Monkey::new(vec![74], &|x| x + 3, 17, 0, 1),
-    ]
+/// Reads the value after `prefix` on the next line of a monkey's notes,
+/// returning the line number, the full line and the value.
+fn field<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    header_line: usize,
+    prefix: &str,
+) -> Result<(usize, &'a str, &'a str), AocError> {
+    let (i, line) = lines.next().ok_or_else(|| {
+        AocError::parse(header_line, 1, format!("Monkey is missing \"{prefix}\""))
+    })?;
+    let value = line
+        .trim_start()
+        .strip_prefix(prefix)
+        .ok_or_else(|| AocError::parse(i + 1, 1, format!("Expected \"{prefix}\"")))?;
+    Ok((i + 1, line, value))
 }
 
-fn setup_input<'a>() -> Vec<Monkey<'a>> {
-    vec![
-        Monkey::new(vec![59, 65, 86, 56, 74, 57, 56], &|x| x * 17, 3, 3, 6),
-        Monkey::new(vec![63, 83, 50, 63, 56], &|x| x + 2, 13, 3, 0),
-        Monkey::new(vec![93, 79, 74, 55], &|x| x + 1, 2, 0, 1),
-        Monkey::new(vec![86, 61, 67, 88, 94, 69, 56, 91], &|x| x + 7, 11, 6, 7),
-        Monkey::new(vec![76, 50, 51], &|x| x * x, 19, 2, 5),
-        Monkey::new(vec![77, 76], &|x| x + 8, 17, 2, 1),
-        Monkey::new(vec![74], &|x| x * 2, 5, 4, 7),
-        Monkey::new(vec![86, 85, 52, 86, 91, 95], &|x| x + 6, 7, 4, 5),
-    ]
+fn number<T: std::str::FromStr>(line_number: usize, line: &str, token: &str) -> Result<T, AocError>
+where
+    T::Err: std::fmt::Display,
+{
+    token
+        .parse::<T>()
+        .map_err(|e| AocError::parse_at(line_number, line, token, format!("Invalid number: {e}")))
 }
 
-#[allow(dead_code)]
-fn print_state(state: &Vec<Monkey>) {
-    for (i, m) in state.iter().enumerate() {
-        println!("Monkey {i}: {:?}", m.items);
+fn parse_monkeys(input: &str) -> Result<Vec<Monkey>, AocError> {
+    let mut monkeys = vec![];
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    while let Some((i, header)) = lines.next() {
+        if !header.starts_with("Monkey ") {
+            return Err(AocError::parse(i + 1, 1, "Expected \"Monkey N:\""));
+        }
+        let (n, line, items) = field(&mut lines, i + 1, "Starting items:")?;
+        let items = items
+            .split(',')
+            .map(|item| number(n, line, item.trim()))
+            .collect::<Result<Vec<u128>, AocError>>()?;
+        let (n, line, operation) = field(&mut lines, i + 1, "Operation: new = old ")?;
+        let operation = match operation.split_once(' ') {
+            Some(("*", "old")) => Operation::Square,
+            Some(("*", y)) => Operation::Multiply(number(n, line, y)?),
+            Some(("+", y)) => Operation::Add(number(n, line, y)?),
+            _ => {
+                return Err(AocError::parse_at(
+                    n,
+                    line,
+                    operation,
+                    format!("Unsupported operation \"{operation}\""),
+                ))
+            }
+        };
+        let (n, line, divisor) = field(&mut lines, i + 1, "Test: divisible by ")?;
+        let divisor = match number(n, line, divisor)? {
+            0 => {
+                return Err(AocError::parse_at(
+                    n,
+                    line,
+                    divisor,
+                    "Cannot divide by zero",
+                ))
+            }
+            d => d,
+        };
+        let (n, line, true_target) = field(&mut lines, i + 1, "If true: throw to monkey ")?;
+        let true_target = number(n, line, true_target)?;
+        let (n, line, false_target) = field(&mut lines, i + 1, "If false: throw to monkey ")?;
+        let false_target = number(n, line, false_target)?;
+        monkeys.push(Monkey::new(
+            items,
+            operation,
+            divisor,
+            true_target,
+            false_target,
+        ));
     }
+    let count = monkeys.len();
+    if let Some(i) = monkeys
+        .iter()
+        .position(|m| m.true_target >= count || m.false_target >= count)
+    {
+        return Err(AocError::unsolvable(format!(
+            "Monkey {i} throws to a monkey that does not exist"
+        )));
+    }
+    Ok(monkeys)
 }
 
-impl<'a> Solution<'a> {
-    fn solve(&self, divide_by_3: bool, n_rounds: u128) -> u128 {
-        let mut state = self.starting_state.clone();
-        let m_count = state.len();
-        let product: u128 = self.starting_state.iter().map(|m| m.divisor).product();
-        #[allow(unused_variables)]
-        for r in 0..n_rounds {
-            for i in 0..m_count {
-                let monkey = state.get_mut(i).unwrap();
-                let mut items = mem::take(&mut monkey.items);
-                monkey.num_inspections += items.len() as u128;
-                let operation = monkey.operation.clone();
-                let divisor = monkey.divisor.clone();
-                let true_target = monkey.true_target;
-                let false_target = monkey.false_target;
-                while let Some(item) = items.pop_front() {
-                    let mut new_value = (*operation)(item);
-                    if divide_by_3 {
-                        new_value /= 3;
-                    } else {
-                        new_value %= product;
-                    }
-                    if new_value % divisor == 0 {
-                        state
-                            .get_mut(true_target)
-                            .unwrap()
-                            .items
-                            .push_back(new_value);
-                    } else {
-                        state
-                            .get_mut(false_target)
-                            .unwrap()
-                            .items
-                            .push_back(new_value);
-                    }
-                }
-            }
-            // println!("After round {}:", r + 1);
-            // print_state(&state);
-            // println!();
+/// The monkeys playing keep away, a round at a time
+pub struct KeepAway {
+    monkeys: Vec<Monkey>,
+    /// Whether relief divides worry levels by 3 after each inspection
+    divide_by_3: bool,
+    /// Worry levels only matter modulo this, the product of the divisors
+    product: u128,
+    round: u128,
+    rounds: u128,
+}
+
+impl KeepAway {
+    fn new(monkeys: &[Monkey], divide_by_3: bool, rounds: u128) -> Result<Self, AocError> {
+        if monkeys.len() < 2 {
+            return Err(AocError::unsolvable("Need at least two monkeys"));
         }
+        Ok(KeepAway {
+            monkeys: monkeys.to_vec(),
+            divide_by_3,
+            product: monkeys.iter().map(|m| m.divisor).product(),
+            round: 0,
+            rounds,
+        })
+    }
 
-        let mut inspections = state
+    fn monkey_business(mut self) -> u128 {
+        while self.step().is_ok_and(|played| played) {
+            trace!("{self}");
+        }
+        let mut inspections = self
+            .monkeys
             .iter()
-            .map(|m| m.num_inspections.clone())
+            .map(|m| m.num_inspections)
             .collect::<Vec<u128>>();
         inspections.sort();
         inspections.reverse();
-        &inspections[0] * &inspections[1]
+        inspections[0] * inspections[1]
     }
 }
 
-impl<'a> Solvable<11> for Solution<'a> {
-    fn new(filename: &str) -> Self {
-        Solution {
-            starting_state: match filename {
-                "test1.txt" => setup_test1(),
-                "input.txt" => setup_input(),
-                _ => panic!("Unknown starting state configuration {filename}"),
-            },
-            filepath: Self::data_path().join(filename),
+impl Simulation for KeepAway {
+    fn step(&mut self) -> Result<bool, AocError> {
+        if self.round == self.rounds {
+            return Ok(false);
         }
+        for i in 0..self.monkeys.len() {
+            let monkey = &mut self.monkeys[i];
+            let mut items = mem::take(&mut monkey.items);
+            monkey.num_inspections += items.len() as u128;
+            let operation = monkey.operation;
+            let divisor = monkey.divisor;
+            let true_target = monkey.true_target;
+            let false_target = monkey.false_target;
+            while let Some(item) = items.pop_front() {
+                let mut new_value = operation.apply(item);
+                if self.divide_by_3 {
+                    new_value /= 3;
+                } else {
+                    new_value %= self.product;
+                }
+                let target = match new_value % divisor {
+                    0 => true_target,
+                    _ => false_target,
+                };
+                self.monkeys[target].items.push_back(new_value);
+            }
+        }
+        self.round += 1;
+        Ok(true)
+    }
+}
+
+impl Display for KeepAway {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "After round {} of {}:", self.round, self.rounds)?;
+        for (i, m) in self.monkeys.iter().enumerate() {
+            write!(
+                f,
+                "\nMonkey {i} inspected items {} times, holding {:?}",
+                m.num_inspections, m.items
+            )?;
+        }
+        Ok(())
     }
+}
+
+impl Solvable<11> for Solution {
+    type Input = Vec<Monkey>;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "rounds1",
+            default: 20,
+            help: "Rounds of keep away in part 1",
+        },
+        Param {
+            name: "rounds2",
+            default: 10_000,
+            help: "Rounds of keep away in part 2",
+        },
+    ];
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        Ok(self.solve(true, 20).to_string())
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        Ok(self.solve(false, 10_000).to_string())
+    fn source(&self) -> &Source {
+        &self.source
     }
-}
 
-#[allow(unused_imports)]
-mod test {
-    use super::*;
+    fn parse(text: &str) -> Result<Vec<Monkey>, AocError> {
+        parse_monkeys(text)
+    }
 
-    #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "10605");
-        Ok(())
+    fn part1(&self, monkeys: &Vec<Monkey>, params: &Params) -> Result<Answer, AocError> {
+        let rounds = params.get("rounds1")?;
+        Ok(KeepAway::new(monkeys, true, rounds)?
+            .monkey_business()
+            .into())
     }
 
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "2713310158");
-        Ok(())
+    fn part2(&self, monkeys: &Vec<Monkey>, params: &Params) -> Result<Answer, AocError> {
+        let rounds = params.get("rounds2")?;
+        Ok(KeepAway::new(monkeys, false, rounds)?
+            .monkey_business()
+            .into())
+    }
+
+    fn simulation<'a>(
+        &self,
+        monkeys: &'a Self::Input,
+        params: &'a Params,
+        part: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        let rounds = params.get(&format!("rounds{part}"))?;
+        Ok(Some(Box::new(KeepAway::new(monkeys, part == 1, rounds)?)))
     }
 }
@@ -1,195 +1,172 @@
-use crate::solvable::Solvable;
-use std::collections::VecDeque;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::grid::{Grid, Pos};
+use crate::params::Params;
+use crate::solvable::{Simulation, Solvable, Source};
 use std::fmt::Display;
-use std::fs;
-use std::{error::Error, path::PathBuf};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-#[derive(Debug, Eq, Hash, PartialEq, Copy, Clone)]
-struct Pos {
-    r: usize,
-    c: usize,
-}
-
-impl Pos {}
-
-#[derive(Debug)]
-struct HeightMap {
+#[derive(Clone, Debug)]
+pub struct HeightMap {
     start: Pos,
     goal: Pos,
     starting_points: Vec<Pos>,
-    rows: usize,
-    columns: usize,
-    height_grid: Vec<Vec<char>>,
-    distance_grid: Option<Vec<Vec<u32>>>,
+    height_grid: Grid<char>,
 }
 
 impl Display for HeightMap {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("Start position: {:?}\n", self.start))?;
-        f.write_fmt(format_args!("Goal position: {:?}\n", self.goal))?;
-        f.write_str("Height Grid:\n")?;
-        for row in &self.height_grid {
-            f.write_fmt(format_args!(
-                "{}\n",
-                row.iter().cloned().collect::<String>()
-            ))?;
-        }
-        if let Some(dg) = &self.distance_grid {
-            f.write_str("\nDistance Grid:\n")?;
-            for row in dg {
-                for d in row {
-                    f.write_fmt(format_args!("{:>3}", d))?;
-                }
-                f.write_str("\n")?;
-            }
-        }
-        Ok(())
+        f.write_fmt(format_args!("Start position: {}\n", self.start))?;
+        f.write_fmt(format_args!("Goal position: {}\n", self.goal))?;
+        f.write_fmt(format_args!("Height Grid:\n{}\n", self.height_grid))
     }
 }
 
 impl HeightMap {
-    fn new(input: &str) -> Self {
-        let mut height_grid = vec![];
-        let mut start = Pos { r: 0, c: 0 };
-        let mut goal = Pos { r: 0, c: 0 };
+    fn new(input: &str) -> Result<Self, AocError> {
+        let mut start = None;
+        let mut goal = None;
         let mut starting_points = vec![];
-        for (r, line) in input.lines().enumerate() {
-            let mut row = vec![];
-            for (c, char) in line.chars().enumerate() {
-                row.push(match char {
-                    'S' => {
-                        start = Pos { r, c };
-                        starting_points.push(Pos { r, c });
-                        'a'
-                    }
-                    'E' => {
-                        goal = Pos { r, c };
-                        'z'
-                    }
-                    'a' => {
-                        starting_points.push(Pos { r, c });
-                        'a'
-                    }
-                    value @ 'b'..='z' => value,
-                    _ => panic!("Unrecognized character {char}"),
-                })
+        let height_grid = Grid::parse(input, |pos, char| match char {
+            'S' => {
+                start = Some(pos);
+                starting_points.push(pos);
+                Ok('a')
             }
-            height_grid.push(row);
-        }
-        HeightMap {
-            start,
-            goal,
+            'E' => {
+                goal = Some(pos);
+                Ok('z')
+            }
+            'a' => {
+                starting_points.push(pos);
+                Ok('a')
+            }
+            value @ 'b'..='z' => Ok(value),
+            _ => Err(format!("Unrecognized character {char}")),
+        })?;
+        let last_line = height_grid.rows();
+        Ok(HeightMap {
+            start: start.ok_or_else(|| AocError::parse(last_line, 1, "No start position S"))?,
+            goal: goal.ok_or_else(|| AocError::parse(last_line, 1, "No goal position E"))?,
             starting_points,
-            rows: height_grid.len(),
-            columns: height_grid.first().unwrap().len(),
             height_grid,
-            distance_grid: None,
-        }
+        })
     }
 
-    fn height_at(&self, p: &Pos) -> char {
-        self.height_grid[p.r][p.c]
+    /// The length of the shortest path from each position to the goal, or
+    /// `u32::MAX` if there is none
+    fn distances(&self) -> Grid<u32> {
+        let mut search = Search::new(self);
+        while search.step().is_ok_and(|spread| spread) {}
+        search.distance_grid
     }
+}
 
-    fn nearby(&self, p: &Pos) -> Vec<Pos> {
-        let mut n = vec![];
-        if p.r > 0 {
-            n.push(Pos { r: p.r - 1, c: p.c });
-        }
-        if p.r < self.rows - 1 {
-            n.push(Pos { r: p.r + 1, c: p.c });
-        }
-        if p.c > 0 {
-            n.push(Pos { r: p.r, c: p.c - 1 });
-        }
-        if p.c < self.columns - 1 {
-            n.push(Pos { r: p.r, c: p.c + 1 });
+/// A breadth first search back from the goal, spreading to every position
+/// at the next distance each step
+pub struct Search<'a> {
+    map: &'a HeightMap,
+    distance_grid: Grid<u32>,
+    frontier: Vec<Pos>,
+}
+
+impl<'a> Search<'a> {
+    fn new(map: &'a HeightMap) -> Self {
+        let heights = &map.height_grid;
+        let mut distance_grid = Grid::new(heights.rows(), heights.columns(), u32::MAX);
+        distance_grid[map.goal] = 0;
+        Search {
+            map,
+            distance_grid,
+            frontier: vec![map.goal],
         }
-        n
     }
+}
 
-    fn calculate_shortest(&mut self) -> u32 {
-        let mut distance_grid: Vec<Vec<u32>> = Vec::with_capacity(self.rows);
-        distance_grid.resize_with(self.rows, || {
-            let mut v = Vec::with_capacity(self.columns);
-            v.resize(self.columns, u32::MAX);
-            v
-        });
-
-        distance_grid[self.goal.r][self.goal.c] = 0;
-        let mut to_visit: VecDeque<Pos> = VecDeque::new();
-        to_visit.push_back(self.goal.clone());
-
-        while let Some(current) = to_visit.pop_front() {
-            let current_height = self.height_at(&current) as u8;
-            let next_distance = distance_grid[current.r][current.c] + 1;
-            for nearby in self.nearby(&current) {
-                if self.height_at(&nearby) as u8 >= current_height - 1
-                    && next_distance < distance_grid[nearby.r][nearby.c]
+impl Simulation for Search<'_> {
+    fn step(&mut self) -> Result<bool, AocError> {
+        if self.frontier.is_empty() {
+            return Ok(false);
+        }
+        let heights = &self.map.height_grid;
+        let mut next = vec![];
+        for &current in &self.frontier {
+            let current_height = heights[current] as u8;
+            let next_distance = self.distance_grid[current] + 1;
+            for nearby in heights.neighbours4(current) {
+                if heights[nearby] as u8 >= current_height - 1
+                    && next_distance < self.distance_grid[nearby]
                 {
-                    distance_grid[nearby.r][nearby.c] = next_distance;
-                    to_visit.push_back(nearby);
+                    self.distance_grid[nearby] = next_distance;
+                    next.push(nearby);
                 }
             }
         }
-
-        let distance = distance_grid[self.start.r][self.start.c];
-        self.distance_grid = Some(distance_grid);
-        distance
+        self.frontier = next;
+        Ok(true)
     }
+}
 
-    fn distance_at(&self, p: &Pos) -> u32 {
-        self.distance_grid.as_ref().unwrap()[p.r][p.c]
+/// The height map, then the distances found so far, with `.` where none is
+impl Display for Search<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let padded = self.distance_grid.map(|&d| match d {
+            u32::MAX => "  .".to_string(),
+            d => format!("{d:>3}"),
+        });
+        f.write_fmt(format_args!("{}\nDistance Grid:\n{padded}\n", self.map))?;
+        f.write_fmt(format_args!(
+            "Positions still to spread from: {}",
+            self.frontier.len()
+        ))
     }
 }
 
 impl Solvable<12> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    type Input = HeightMap;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut height_map = HeightMap::new(&input);
-        let distance = height_map.calculate_shortest();
-        Ok(distance.to_string())
+    fn source(&self) -> &Source {
+        &self.source
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut height_map = HeightMap::new(&input);
-        height_map.calculate_shortest();
+    fn parse(text: &str) -> Result<HeightMap, AocError> {
+        HeightMap::new(text)
+    }
+
+    fn part1(&self, height_map: &HeightMap, _: &Params) -> Result<Answer, AocError> {
+        match height_map.distances()[height_map.start] {
+            u32::MAX => Err(AocError::unsolvable(
+                "The goal cannot be reached from the start",
+            )),
+            distance => Ok(distance.into()),
+        }
+    }
+
+    fn part2(&self, height_map: &HeightMap, _: &Params) -> Result<Answer, AocError> {
+        let distances = height_map.distances();
         let distance = height_map
             .starting_points
             .iter()
-            .map(|p| height_map.distance_at(p))
+            .map(|&p| distances[p])
             .min()
-            .unwrap();
-        Ok(distance.to_string())
-    }
-}
-
-#[allow(unused_imports)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "31");
-        Ok(())
+            .filter(|&d| d != u32::MAX)
+            .ok_or_else(|| AocError::unsolvable("The goal cannot be reached from any 'a'"))?;
+        Ok(distance.into())
     }
 
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "29");
-        Ok(())
+    fn simulation<'a>(
+        &self,
+        height_map: &'a HeightMap,
+        _: &'a Params,
+        _: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        Ok(Some(Box::new(Search::new(height_map))))
     }
 }
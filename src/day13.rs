@@ -1,13 +1,14 @@
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::solvable::{Solvable, Source};
+use log::{debug, trace};
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Result as FmtResult, Write};
-use std::fs;
-use std::{error::Error, path::PathBuf};
-
-const DEBUG: bool = false;
+use std::str::FromStr;
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -41,49 +42,41 @@ impl Display for PacketData {
     }
 }
 
-impl PartialOrd for PacketData {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if DEBUG {
-            println!("Compare {} vs {}", self, other);
-        }
+impl Ord for PacketData {
+    fn cmp(&self, other: &Self) -> Ordering {
+        trace!("Compare {} vs {}", self, other);
         match (self, other) {
             (Integer(a), Integer(b)) => {
-                if DEBUG {
-                    println!(" - Integer({}) vs Integer({})", a, b);
-                }
-                Some(a.cmp(b))
+                trace!(" - Integer({}) vs Integer({})", a, b);
+                a.cmp(b)
             }
             (List(pda), List(pdb)) => {
-                if DEBUG {
-                    println!(" - List({:?}) vs List({:?})", pda, pdb);
-                }
+                trace!(" - List({:?}) vs List({:?})", pda, pdb);
                 for (left, right) in pda.iter().zip(pdb) {
-                    if DEBUG {
-                        println!(" - - {:?} vs {:?}", left, right);
-                    }
+                    trace!(" - - {:?} vs {:?}", left, right);
                     match left.cmp(right) {
                         o @ Ordering::Less | o @ Ordering::Greater => {
-                            return Some(o);
+                            return o;
                         }
                         _ => {}
                     }
                 }
-                return Some(pda.len().cmp(&pdb.len()));
+                pda.len().cmp(&pdb.len())
             }
-            (a @ Integer(..), b @ List(..)) => Some(List(vec![a.clone()]).cmp(b)),
-            (a @ List(..), b @ Integer(..)) => Some(a.cmp(&List(vec![b.clone()]))),
+            (a @ Integer(..), b @ List(..)) => List(vec![a.clone()]).cmp(b),
+            (a @ List(..), b @ Integer(..)) => a.cmp(&List(vec![b.clone()])),
         }
     }
 }
 
-impl Ord for PacketData {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+impl PartialOrd for PacketData {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
-struct Packet(PacketData);
+pub struct Packet(PacketData);
 
 impl Display for Packet {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
@@ -91,108 +84,147 @@ impl Display for Packet {
     }
 }
 
-impl From<&str> for Packet {
-    fn from(input: &str) -> Self {
-        let mut chars = input.chars().peekable();
+impl Packet {
+    /// Parse a packet found on `line_number` of the input, reporting errors
+    /// at the column of the offending character. Whitespace around the
+    /// packet is ignored.
+    fn parse(line_number: usize, input: &str) -> Result<Self, AocError> {
+        let error = |column: usize, reason: String| AocError::parse(line_number, column, reason);
+        let input = input.trim_end();
+        let mut chars = input
+            .chars()
+            .enumerate()
+            .skip_while(|(_, c)| c.is_whitespace())
+            .peekable();
         let mut list_stacks: Vec<PacketData> = vec![];
         loop {
-            // if DEBUG {
-            //     println!("List stacks:");
-            //     for ls in &list_stacks {
-            //         println!("{:?}", ls);
-            //     }
-            // }
             match chars.next() {
-                Some('[') => list_stacks.push(PacketData::new_list()),
-                Some(']') => {
-                    let closed_stack = list_stacks.pop().unwrap();
+                Some((_, '[')) => list_stacks.push(PacketData::new_list()),
+                Some((i, ']')) => {
+                    let closed_stack = list_stacks
+                        .pop()
+                        .ok_or_else(|| error(i + 1, "Unmatched ']'".to_string()))?;
                     if let Some(&mut PacketData::List(ref mut top_stack)) = list_stacks.last_mut() {
                         top_stack.push(closed_stack);
+                    } else if let Some((j, c)) = chars.next() {
+                        return Err(error(j + 1, format!("Unexpected {c:?} after packet")));
                     } else {
-                        return Self(closed_stack);
+                        return Ok(Self(closed_stack));
                     }
                 }
-                Some(',') => {}
-                Some(c @ '0'..='9') => {
+                Some((_, ',')) => {}
+                Some((i, c @ '0'..='9')) => {
                     let mut number = c.to_string();
-                    loop {
-                        match chars.peek().unwrap() {
-                            d @ '0'..='9' => {
-                                number.push(*d);
-                                chars.next();
-                            }
-                            _ => break,
-                        }
+                    while let Some((_, d @ '0'..='9')) = chars.peek() {
+                        number.push(*d);
+                        chars.next();
                     }
-                    if let Some(&mut PacketData::List(ref mut top_stack)) = list_stacks.last_mut() {
-                        top_stack.push(PacketData::Integer(
-                            number.parse::<u32>().expect("Unrecognized number sequence"),
-                        ));
+                    let value = number
+                        .parse::<u32>()
+                        .map_err(|e| error(i + 1, format!("Invalid integer {number}: {e}")))?;
+                    match list_stacks.last_mut() {
+                        Some(PacketData::List(top_stack)) => top_stack.push(Integer(value)),
+                        _ => return Err(error(i + 1, "Integer outside of a list".to_string())),
                     }
                 }
                 None => break,
-                Some(c) => panic!("Unexpected character {}", c),
+                Some((i, c)) => return Err(error(i + 1, format!("Unexpected character {c:?}"))),
             }
         }
-        panic!("Malformed input {}", input);
+        Err(error(
+            input.chars().count().max(1),
+            format!("Malformed packet {input}"),
+        ))
     }
 }
 
+impl FromStr for Packet {
+    type Err = AocError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Packet::parse(1, input)
+    }
+}
+
+/// Groups the packets of each blank-line-separated block, along with the
+/// line number the block starts on.
+fn parse_packet_groups(input: &str) -> Result<Vec<(usize, Vec<Packet>)>, AocError> {
+    let mut groups: Vec<(usize, Vec<Packet>)> = vec![];
+    let mut in_group = false;
+    for (i, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            in_group = false;
+            continue;
+        }
+        if !in_group {
+            groups.push((i + 1, vec![]));
+            in_group = true;
+        }
+        if let Some((_, group)) = groups.last_mut() {
+            group.push(Packet::parse(i + 1, line)?);
+        }
+    }
+    Ok(groups)
+}
+
+fn packet_pairs(groups: &[(usize, Vec<Packet>)]) -> Result<Vec<(&Packet, &Packet)>, AocError> {
+    groups
+        .iter()
+        .map(|(line, group)| match &group[..] {
+            [left, right] => Ok((left, right)),
+            _ => Err(AocError::parse(*line, 1, "Expected a pair of packets")),
+        })
+        .collect()
+}
+
 impl PartialOrd for Packet {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for Packet {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap()
+        self.0.cmp(&other.0)
     }
 }
 
 impl Solvable<13> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    type Input = Vec<(usize, Vec<Packet>)>;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
+    }
+
+    fn source(&self) -> &Source {
+        &self.source
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let pairs = input.split("\n\n");
+    fn parse(text: &str) -> Result<Vec<(usize, Vec<Packet>)>, AocError> {
+        parse_packet_groups(text)
+    }
+
+    fn part1(&self, groups: &Self::Input, _: &Params) -> Result<Answer, AocError> {
+        let pairs = packet_pairs(groups)?;
         let mut in_order: Vec<usize> = vec![];
-        for (i, pair) in pairs.enumerate() {
-            let mut split = pair.splitn(2, "\n");
-            let left = Packet::from(split.next().unwrap());
-            let right = Packet::from(split.next().unwrap());
-            if DEBUG {
-                println!("Comparing {} vs {}", left, right);
-            }
+        for (i, (left, right)) in pairs.iter().enumerate() {
             if left < right {
-                if DEBUG {
-                    println!("In order");
-                }
+                debug!("Pair {}: {} vs {}: in order", i + 1, left, right);
                 in_order.push(i + 1);
             } else {
-                if DEBUG {
-                    println!("Out of order");
-                }
+                debug!("Pair {}: {} vs {}: out of order", i + 1, left, right);
             }
         }
-        Ok(in_order.iter().sum::<usize>().to_string())
+        Ok(in_order.iter().sum::<usize>().into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut packets = input
-            .lines()
-            .filter_map(|l| match l.trim() {
-                "" => None,
-                p @ _ => Some(Packet::from(p)),
-            })
+    fn part2(&self, groups: &Self::Input, _: &Params) -> Result<Answer, AocError> {
+        let mut packets = groups
+            .iter()
+            .flat_map(|(_, group)| group.iter().cloned())
             .collect::<Vec<Packet>>();
-        let divider1 = Packet::from("[[2]]");
-        let divider2 = Packet::from("[[6]]");
+        let divider1 = Packet::from_str("[[2]]")?;
+        let divider2 = Packet::from_str("[[6]]")?;
         packets.push(divider1.clone());
         packets.push(divider2.clone());
         packets.sort();
@@ -202,90 +234,109 @@ impl Solvable<13> for Solution {
                 product *= i + 1
             }
         }
-        Ok(product.to_string())
+        Ok(product.into())
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::error::Error;
 
     #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "13");
-        Ok(())
-    }
-
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "140");
+    fn packet_from_empty_bracket_string() -> Result<(), Box<dyn Error>> {
+        assert_eq!(Packet::from_str("[]")?, Packet(List(vec![])));
         Ok(())
     }
 
     #[test]
-    fn packet_from_empty_bracket_string() {
-        assert_eq!(Packet::from("[]"), Packet(List(vec![])));
-    }
-
-    #[test]
-    fn packet_from_list_of_integers() {
+    fn packet_from_list_of_integers() -> Result<(), Box<dyn Error>> {
         assert_eq!(
-            Packet::from("[1,2,3]"),
+            Packet::from_str("[1,2,3]")?,
             Packet(List(vec![Integer(1), Integer(2), Integer(3)]))
-        )
+        );
+        Ok(())
     }
 
     #[test]
-    fn packet_from_list_of_single_list() {
-        assert_eq!(Packet::from("[[]]"), Packet(List(vec![List(vec![])])))
+    fn packet_from_list_of_single_list() -> Result<(), Box<dyn Error>> {
+        assert_eq!(Packet::from_str("[[]]")?, Packet(List(vec![List(vec![])])));
+        Ok(())
     }
 
     #[test]
-    fn packet_from_list_of_lists() {
+    fn packet_from_list_of_lists() -> Result<(), Box<dyn Error>> {
         assert_eq!(
-            Packet::from("[[],[1,2,3]]"),
+            Packet::from_str("[[],[1,2,3]]")?,
             Packet(List(vec![
                 List(vec![]),
                 List(vec![Integer(1), Integer(2), Integer(3)])
             ]))
         );
+        Ok(())
     }
 
     #[test]
-    fn packet_from_list_with_multiple_digits() {
+    fn packet_from_list_with_multiple_digits() -> Result<(), Box<dyn Error>> {
         assert_eq!(
-            Packet::from("[12,384,3]"),
+            Packet::from_str("[12,384,3]")?,
             Packet(List(vec![Integer(12), Integer(384), Integer(3)]))
         );
+        Ok(())
+    }
+
+    #[test]
+    fn reports_columns_on_indented_lines() {
+        let error = parse_packet_groups("[1]\n  [1,x]\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "line 2, column 6: Unexpected character 'x'");
+        assert!(parse_packet_groups("  [1]  \n[2]\n").is_ok());
     }
 
     #[test]
-    fn packet_pair1_should_compare_correctly() {
-        let left = Packet::from("[1,1,3,1,1]");
-        let right = Packet::from("[1,1,5,1,1]");
+    fn packet_pair1_should_compare_correctly() -> Result<(), Box<dyn Error>> {
+        let left = Packet::from_str("[1,1,3,1,1]")?;
+        let right = Packet::from_str("[1,1,5,1,1]")?;
         assert!(left < right);
+        Ok(())
     }
 
     #[test]
-    fn packet_pair2_should_compare_correctly() {
-        let left = Packet::from("[[1],[2,3,4]]");
-        let right = Packet::from("[[1],4]");
+    fn packet_pair2_should_compare_correctly() -> Result<(), Box<dyn Error>> {
+        let left = Packet::from_str("[[1],[2,3,4]]")?;
+        let right = Packet::from_str("[[1],4]")?;
         assert!(left < right);
+        Ok(())
     }
 
     #[test]
-    fn packet_pair3_should_compare_correctly() {
-        let left = Packet::from("[9]");
-        let right = Packet::from("[[8,7,6]]");
+    fn packet_pair3_should_compare_correctly() -> Result<(), Box<dyn Error>> {
+        let left = Packet::from_str("[9]")?;
+        let right = Packet::from_str("[[8,7,6]]")?;
         assert!(left > right);
+        Ok(())
     }
 
     #[test]
-    fn packet_pair4_should_compare_correctly() {
-        let left = Packet::from("[9]");
-        let right = Packet::from("[[8,7,6]]");
+    fn packet_pair4_should_compare_correctly() -> Result<(), Box<dyn Error>> {
+        let left = Packet::from_str("[9]")?;
+        let right = Packet::from_str("[[8,7,6]]")?;
         assert!(left > right);
+        Ok(())
+    }
+
+    #[test]
+    fn packet_reports_the_column_of_an_unexpected_character() {
+        match Packet::parse(7, "[1,[2,x]]") {
+            Err(AocError::Parse { line, column, .. }) => assert_eq!((line, column), (7, 7)),
+            _ => panic!("Expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn packet_rejects_unbalanced_brackets() {
+        assert!(Packet::from_str("[[1]").is_err());
+        assert!(Packet::from_str("[1]]").is_err());
     }
 }
@@ -1,148 +1,252 @@
-use lazy_regex::{regex, Captures, Lazy, Regex};
-
-use crate::solvable::Solvable;
-use std::collections::HashSet;
-use std::fs;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::geom::{Bounds, Point2};
+use crate::grid::{Grid, Pos};
+use crate::params::Params;
+use crate::parse::lines;
+use crate::solvable::{Simulation, Solvable, Source};
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::mem::swap;
-use std::{error::Error, path::PathBuf};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
+}
+
+type Point = Point2<i32>;
+
+const SOURCE: Point = Point2 { x: 500, y: 0 };
+
+/// The deepest rock accepted, which keeps the cave to a few million tiles
+const MAX_DEPTH: i32 = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    Air,
+    Rock,
+    Sand,
 }
 
-static LINE_REGEX: &Lazy<Regex> = regex!(r"(\d+,\d+)");
+impl Display for Tile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let c = match self {
+            Tile::Air => '.',
+            Tile::Rock => '#',
+            Tile::Sand => 'o',
+        };
+        write!(f, "{c}")
+    }
+}
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
-struct Point {
-    x: usize,
-    y: usize,
+/// The part of the cave sand can reach, down to just above the floor
+#[derive(Clone, Debug)]
+pub struct Cave {
+    grid: Grid<Tile>,
+    /// The x coordinate of the grid's first column
+    left: i32,
+    /// The row of the lowest rock
+    lowest: usize,
 }
 
-impl From<&str> for Point {
-    fn from(input: &str) -> Self {
-        let mut parts = input.split(',');
-        let x = parts
-            .next()
-            .unwrap()
-            .parse::<usize>()
-            .expect("Malformed x coord");
-        let y = parts
-            .next()
-            .unwrap()
-            .parse::<usize>()
-            .expect("Malformed y coord");
-        Point { x, y }
+impl Cave {
+    fn new(rock: &[Point]) -> Self {
+        let lowest = Bounds::of(rock.iter().copied()).map_or(0, |b| b.max.y);
+        // Sand moves at most one column sideways for each row it falls, so
+        // above the floor it stays within `floor` columns of the source
+        let floor = lowest + 2;
+        let mut cave = Cave {
+            grid: Grid::new(floor as usize, 2 * floor as usize + 1, Tile::Air),
+            left: SOURCE.x - floor,
+            lowest: lowest as usize,
+        };
+        for &p in rock {
+            if let Some(tile) = cave.pos(p).and_then(|pos| cave.grid.get_mut(pos)) {
+                *tile = Tile::Rock;
+            }
+        }
+        cave
+    }
+
+    fn pos(&self, p: Point) -> Option<Pos> {
+        Some(Point2::new(
+            usize::try_from(p.x - self.left).ok()?,
+            usize::try_from(p.y).ok()?,
+        ))
     }
 }
 
-fn drop_sand(
-    cave: &HashSet<Point>,
-    start: &Point,
-    y_bound: usize,
+/// Sand poured into the cave a grain at a time, until a grain falls past
+/// the lowest rock or, resting on the floor, until the source is blocked
+pub struct Pour {
+    cave: Cave,
     with_floor: bool,
-) -> Option<Point> {
-    let mut pos = start.clone();
-    let floor = if with_floor { y_bound + 2 } else { y_bound + 1 };
-    for y in start.y..=floor {
-        if with_floor && y == floor {
-            pos.y = floor - 1;
-            return Some(pos);
+    /// The path the current grain has fallen along. Each grain follows the
+    /// one before it, so it can start where that one was before it stopped.
+    path: Vec<Pos>,
+    resting: usize,
+}
+
+impl Pour {
+    fn new(cave: &Cave, with_floor: bool) -> Self {
+        Pour {
+            cave: cave.clone(),
+            with_floor,
+            path: cave.pos(SOURCE).into_iter().collect(),
+            resting: 0,
         }
-        if cave.contains(&Point { x: pos.x, y }) {
-            if !cave.contains(&Point { x: pos.x - 1, y }) {
-                pos.x -= 1;
-            } else if !cave.contains(&Point { x: pos.x + 1, y }) {
-                pos.x += 1;
-            } else {
-                pos.y = y - 1;
-                return Some(pos);
+    }
+
+    /// How many grains come to rest
+    fn run(mut self) -> usize {
+        while self.step().is_ok_and(|poured| poured) {}
+        self.resting
+    }
+}
+
+impl Simulation for Pour {
+    /// Let one more grain come to rest
+    fn step(&mut self) -> Result<bool, AocError> {
+        let grid = &mut self.cave.grid;
+        while let Some(&pos) = self.path.last() {
+            if !self.with_floor && pos.y > self.cave.lowest {
+                return Ok(false);
+            }
+            // Below the grid is the floor
+            let next = [Point2::new(0, 1), Point2::new(-1, 1), Point2::new(1, 1)]
+                .into_iter()
+                .filter_map(|step| grid.step(pos, step))
+                .find(|&p| grid[p] == Tile::Air);
+            match next {
+                Some(p) => self.path.push(p),
+                None => {
+                    grid[pos] = Tile::Sand;
+                    self.path.pop();
+                    self.resting += 1;
+                    return Ok(true);
+                }
             }
         }
+        Ok(false)
     }
-    None
 }
 
-impl Solution {
-    fn solve(&self, input: &str, with_floor: bool) -> i32 {
-        let mut cave: HashSet<Point> = HashSet::new();
-        let mut highest_y = 0;
-        for line in input.lines() {
-            let captures: &[Captures] =
-                &LINE_REGEX.captures_iter(line).collect::<Vec<Captures>>()[..];
-            for points in captures.windows(2) {
-                let (mut from, mut to) = (
-                    Point::from(points[0].get(0).unwrap().as_str()),
-                    Point::from(points[1].get(0).unwrap().as_str()),
-                );
-                if from.y > highest_y {
-                    highest_y = from.y;
-                }
-                if to.y > highest_y {
-                    highest_y = to.y;
-                }
-                if from.x == to.x {
-                    if from.y > to.y {
-                        swap(&mut from, &mut to);
-                    }
-                    for y in from.y..=to.y {
-                        cave.insert(Point { x: from.x, y });
-                    }
+/// The columns of the cave with rock or sand in them, and a count
+impl Display for Pour {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let grid = &self.cave.grid;
+        let used = grid
+            .iter()
+            .filter(|(_, &tile)| tile != Tile::Air)
+            .map(|(p, _)| p.x);
+        let (left, right) = used.fold((usize::MAX, 0), |(l, r), x| (l.min(x), r.max(x)));
+        for y in 0..grid.rows() {
+            let row = (left..=right)
+                .map(|x| grid[Point2::new(x, y)].to_string())
+                .collect::<String>();
+            writeln!(f, "{row}")?;
+        }
+        write!(f, "{} grains of sand at rest", self.resting)
+    }
+}
+
+impl Display for Cave {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.grid)
+    }
+}
+
+fn parse_cave(input: &str) -> Result<Cave, AocError> {
+    let mut rock = vec![];
+    for line in lines(input) {
+        let points = line
+            .text
+            .split(" -> ")
+            .map(|token| {
+                let p = Point::parse_at(line.number, line.text, token)?;
+                // Sand can't reach further sideways than the floor is deep
+                let reach = SOURCE.x - MAX_DEPTH - 2..=SOURCE.x + MAX_DEPTH + 2;
+                if !(0..=MAX_DEPTH).contains(&p.y) {
+                    Err(line.error_at(token, format!("Depth {} is not in 0..={MAX_DEPTH}", p.y)))
+                } else if !reach.contains(&p.x) {
+                    Err(line.error_at(token, format!("{} is out of the sand's reach", p.x)))
                 } else {
-                    if from.x > to.x {
-                        swap(&mut from, &mut to);
-                    }
-                    for x in from.x..=to.x {
-                        cave.insert(Point { x, y: from.y });
-                    }
+                    Ok(p)
                 }
+            })
+            .collect::<Result<Vec<Point>, AocError>>()?;
+        for points in points.windows(2) {
+            let (mut from, mut to) = (points[0], points[1]);
+            if from.x != to.x && from.y != to.y {
+                return Err(line.error("Paths must be horizontal or vertical"));
             }
-        }
-        let sand_start = Point { x: 500, y: 0 };
-        let mut num_sand = 0;
-        while let Some(sand_stop) = drop_sand(&cave, &sand_start, highest_y, with_floor) {
-            cave.insert(sand_stop);
-            num_sand += 1;
-            if with_floor && sand_stop == sand_start {
-                return num_sand;
+            if from.x == to.x {
+                if from.y > to.y {
+                    swap(&mut from, &mut to);
+                }
+                rock.extend((from.y..=to.y).map(|y| Point2::new(from.x, y)));
+            } else {
+                if from.x > to.x {
+                    swap(&mut from, &mut to);
+                }
+                rock.extend((from.x..=to.x).map(|x| Point2::new(x, from.y)));
             }
         }
-        num_sand
     }
+    Ok(Cave::new(&rock))
 }
 
 impl Solvable<14> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    type Input = Cave;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
+    }
+
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Cave, AocError> {
+        parse_cave(text)
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(self.solve(&input, false).to_string())
+    fn part1(&self, cave: &Cave, _: &Params) -> Result<Answer, AocError> {
+        Ok(Pour::new(cave, false).run().into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(self.solve(&input, true).to_string())
+    fn part2(&self, cave: &Cave, _: &Params) -> Result<Answer, AocError> {
+        Ok(Pour::new(cave, true).run().into())
+    }
+
+    fn simulation<'a>(
+        &self,
+        cave: &'a Cave,
+        _: &'a Params,
+        part: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        Ok(Some(Box::new(Pour::new(cave, part == 2))))
     }
 }
 
 #[cfg(test)]
+#[allow(unused_imports)]
 mod test {
     use super::*;
 
     #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "24");
-        Ok(())
-    }
-
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "93");
-        Ok(())
+    fn rejects_rock_outside_the_cave() {
+        let error = |text| parse_cave(text).unwrap_err().to_string();
+        assert_eq!(
+            error("498,4 -> 498,6\n500,-5 -> 502,-5"),
+            "line 2, column 1: Depth -5 is not in 0..=1000"
+        );
+        assert_eq!(
+            error("500,2 -> 500,2000000000"),
+            "line 1, column 10: Depth 2000000000 is not in 0..=1000"
+        );
+        assert_eq!(
+            error("-2147483648,3 -> 500,3"),
+            "line 1, column 1: -2147483648 is out of the sand's reach"
+        );
+        assert!(parse_cave("498,1000 -> 498,999").is_ok());
     }
 }
@@ -1,179 +1,152 @@
-use lazy_regex::{regex, Captures, Lazy, Regex};
-
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::geom::{Bounds, Point2};
+use crate::params::{Param, Params};
+use crate::parse::lines;
+use crate::solvable::{Solvable, Source};
+use log::debug;
 use std::collections::HashSet;
-use std::fs;
-use std::hash::Hash;
-use std::{error::Error, path::PathBuf};
 
 pub struct Solution {
-    filepath: PathBuf,
-    row_to_inspect: i32,
-    limit: i32,
-}
-
-static LINE_REGEX: &Lazy<Regex> = regex!(r"Sensor at (.+): closest beacon is at (.+)");
-
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct Point {
-    x: i32,
-    y: i32,
+    source: Source,
 }
 
-impl From<&str> for Point {
-    fn from(s: &str) -> Self {
-        let mut location_parts = s.split(", ");
-        let location_x = location_parts.next().unwrap();
-        let location_y = location_parts.next().unwrap();
-
-        Point {
-            x: location_x[2..].parse::<i32>().expect("Invalid x"),
-            y: location_y[2..].parse::<i32>().expect("Invalid y"),
-        }
-    }
-}
-
-impl Point {
-    fn distance_to(&self, other: &Self) -> u32 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
-}
+type Point = Point2<i32>;
 
 #[derive(Debug)]
-struct Sensor {
+pub struct Sensor {
     location: Point,
     beacon_location: Point,
-    beacon_distance: u32,
+    beacon_distance: i32,
 }
 
 impl Sensor {
-    fn x_bounds(&self) -> (i32, i32) {
-        (
-            self.location.x - self.beacon_distance as i32,
-            self.location.x + self.beacon_distance as i32,
-        )
+    /// The rectangle around the diamond of positions the sensor covers
+    fn bounds(&self) -> Bounds<i32> {
+        let reach = Point2::new(self.beacon_distance, self.beacon_distance);
+        Bounds {
+            min: self.location - reach,
+            max: self.location + reach,
+        }
     }
 
     fn in_range(&self, p: &Point) -> bool {
-        self.location.distance_to(p) <= self.beacon_distance
+        self.location.manhattan(p) <= self.beacon_distance
     }
 
     fn points_on_range_radius(&self, limit: i32) -> Vec<Point> {
-        let radius_distance = self.beacon_distance as i32 + 1;
+        let radius_distance = self.beacon_distance + 1;
         let mut points: HashSet<Point> = HashSet::new();
         for x in (self.location.x - radius_distance).max(0)
             ..=(self.location.x + radius_distance).min(limit)
         {
-            let d = radius_distance - self.location.x.abs_diff(x) as i32;
+            let d = radius_distance - (self.location.x - x).abs();
             if self.location.y + d <= limit {
-                points.insert(Point {
-                    x,
-                    y: self.location.y + d,
-                });
+                points.insert(Point2::new(x, self.location.y + d));
             }
             if self.location.y - d >= 0 {
-                points.insert(Point {
-                    x,
-                    y: self.location.y - d,
-                });
+                points.insert(Point2::new(x, self.location.y - d));
             }
         }
         points.into_iter().collect::<Vec<Point>>()
     }
 }
 
-impl From<Captures<'_>> for Sensor {
-    fn from(c: Captures) -> Self {
-        let location = Point::from(c.get(1).unwrap().as_str());
-        let beacon_location = Point::from(c.get(2).unwrap().as_str());
-        let beacon_distance = location.distance_to(&beacon_location);
-
-        Self {
+fn parse_sensors(input: &str) -> Result<Vec<Sensor>, AocError> {
+    let mut sensors = vec![];
+    for line in lines(input) {
+        let [location, beacon] = line.template("Sensor at {}: closest beacon is at {}")?;
+        let location = Point::parse_at(line.number, line.text, location)?;
+        let beacon_location = Point::parse_at(line.number, line.text, beacon)?;
+        let beacon_distance = location.manhattan(&beacon_location);
+        sensors.push(Sensor {
             location,
             beacon_location,
             beacon_distance,
-        }
+        });
     }
+    Ok(sensors)
 }
 
-impl Solution {
-    pub fn set_row_to_inspect(&mut self, row: i32) {
-        self.row_to_inspect = row;
+impl Solvable<15> for Solution {
+    type Input = Vec<Sensor>;
+
+    // The example inspects a different row and area; see test1.params.toml
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "row",
+            default: 2_000_000,
+            help: "Row to count positions without a beacon in",
+        },
+        Param {
+            name: "limit",
+            default: 4_000_000,
+            help: "Largest x and y the distress beacon can have",
+        },
+    ];
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    pub fn set_limit(&mut self, limit: i32) {
-        self.limit = limit;
+    fn source(&self) -> &Source {
+        &self.source
     }
-}
 
-impl Solvable<15> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-            row_to_inspect: 10,
-            limit: 20,
-        }
+    fn parse(text: &str) -> Result<Vec<Sensor>, AocError> {
+        parse_sensors(text)
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut sensors: Vec<Sensor> = Vec::new();
-        let mut max_left: i32 = 0;
-        let mut max_right: i32 = 0;
-        let mut beacon_locations: HashSet<Point> = HashSet::new();
-        for line in input.lines() {
-            let captures = LINE_REGEX.captures(line).unwrap();
-            let sensor = Sensor::from(captures);
-            beacon_locations.insert(sensor.beacon_location.clone());
-            let (left_bound, right_bound) = sensor.x_bounds();
-            max_left = max_left.min(left_bound);
-            max_right = max_right.max(right_bound);
-
-            sensors.push(sensor);
-        }
+    fn part1(&self, sensors: &Vec<Sensor>, params: &Params) -> Result<Answer, AocError> {
+        let row: i32 = params.get("row")?;
+        let beacon_locations: HashSet<Point> = sensors.iter().map(|s| s.beacon_location).collect();
+        let corners = sensors.iter().flat_map(|s| {
+            let b = s.bounds();
+            [b.min, b.max]
+        });
+        let Some(covered) = Bounds::of(corners) else {
+            return Ok(0.into());
+        };
         let mut num_invalid = 0;
-        for x in max_left..=max_right {
-            let p = Point {
-                x,
-                y: self.row_to_inspect,
-            };
+        for x in covered.min.x..=covered.max.x {
+            let p = Point2::new(x, row);
             if beacon_locations.contains(&p) {
                 continue;
             }
-            for sensor in &sensors {
+            for sensor in sensors {
                 if sensor.in_range(&p) {
                     num_invalid += 1;
                     break;
                 }
             }
         }
-        Ok(num_invalid.to_string())
+        Ok(num_invalid.into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
+    fn part2(&self, sensors: &Vec<Sensor>, params: &Params) -> Result<Answer, AocError> {
+        let limit: i32 = params.get("limit")?;
         let mut possible_locations: HashSet<Point> = HashSet::new();
-        let mut sensors = Vec::new();
 
-        println!("generating possible locations...");
-        for line in input.lines() {
-            let captures = LINE_REGEX.captures(line).unwrap();
-            let sensor = Sensor::from(captures);
-            for p in sensor.points_on_range_radius(self.limit) {
+        debug!("Generating possible locations...");
+        for sensor in sensors {
+            for p in sensor.points_on_range_radius(limit) {
                 possible_locations.insert(p);
             }
-            sensors.push(sensor);
         }
-        println!("locations to consider: {}", possible_locations.len());
+        debug!("Locations to consider: {}", possible_locations.len());
         // Eliminate points that are out of bounds
+        let area = Bounds {
+            min: Point::ORIGIN,
+            max: Point2::new(limit, limit),
+        };
         let locations_in_bounds: Vec<Point> = possible_locations
             .into_iter()
-            .filter(|pl| pl.x >= 0 && pl.x <= self.limit && pl.y >= 0 && pl.y <= self.limit)
+            .filter(|pl| area.contains(pl))
             .collect();
-        println!("locations in bounds: {}", locations_in_bounds.len());
+        debug!("Locations in bounds: {}", locations_in_bounds.len());
         let mut locations = Vec::new();
         'locations: for location in locations_in_bounds {
-            for s in &sensors {
+            for s in sensors {
                 if s.in_range(&location) {
                     continue 'locations;
                 }
@@ -181,28 +154,13 @@ impl Solvable<15> for Solution {
             locations.push(location);
         }
 
-        assert_eq!(locations.len(), 1);
-        let final_location = locations.iter().next().unwrap();
+        let [final_location] = &locations[..] else {
+            return Err(AocError::unsolvable(format!(
+                "Expected exactly one possible beacon location, found {}",
+                locations.len()
+            )));
+        };
         let answer: i128 = final_location.x as i128 * 4_000_000 + final_location.y as i128;
-        Ok(answer.to_string())
-    }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "26");
-        Ok(())
-    }
-
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "56000011");
-        Ok(())
+        Ok(answer.into())
     }
 }
@@ -1,45 +1,50 @@
 use std::cmp::Ordering;
-use std::error::Error;
-use std::fs;
-use std::path::PathBuf;
 
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::solvable::{Solvable, Source};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-#[derive(Eq, PartialEq)]
-enum Shape {
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Shape {
     Rock = 1,
     Paper = 2,
     Scissors = 3,
 }
 
-impl From<char> for Shape {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Shape {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'A' | 'X' => Self::Rock,
-            'B' | 'Y' => Self::Paper,
-            'C' | 'Z' => Self::Scissors,
-            _ => panic!("Unrecognized character \"{}\"", c),
+            'A' | 'X' => Ok(Self::Rock),
+            'B' | 'Y' => Ok(Self::Paper),
+            'C' | 'Z' => Ok(Self::Scissors),
+            _ => Err(format!("Unrecognized Shape character \"{}\"", c)),
         }
     }
 }
 
-enum Outcome {
+#[derive(Clone, Copy)]
+pub enum Outcome {
     Lose,
     Draw,
     Win,
 }
 
-impl From<char> for Outcome {
-    fn from(c: char) -> Self {
+impl TryFrom<char> for Outcome {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
-            'X' => Self::Lose,
-            'Y' => Self::Draw,
-            'Z' => Self::Win,
-            _ => panic!("Unrecognized Outcome character \"{}\"", c),
+            'X' => Ok(Self::Lose),
+            'Y' => Ok(Self::Draw),
+            'Z' => Ok(Self::Win),
+            _ => Err(format!("Unrecognized Outcome character \"{}\"", c)),
         }
     }
 }
@@ -66,54 +71,95 @@ impl PartialOrd for Shape {
     }
 }
 
-impl Solution {
-    fn score_from_lines<F>(&self, score_from_chars: F) -> Result<u32, Box<dyn Error>>
-    where
-        F: Fn(char, char) -> u32,
-    {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut score = 0;
-        for line in input.lines() {
-            let chars = line.chars().into_iter().collect::<Vec<char>>();
-            score += score_from_chars(chars[0], chars[2]);
-        }
-        Ok(score)
+/// Parse a round of two columns, reporting the column of whichever
+/// character could not be converted.
+fn parse_round<A, B>(line_number: usize, line: &str) -> Result<(A, B), AocError>
+where
+    A: TryFrom<char, Error = String>,
+    B: TryFrom<char, Error = String>,
+{
+    let chars = line.chars().collect::<Vec<char>>();
+    match chars[..] {
+        [c1, ' ', c2] => Ok((
+            A::try_from(c1).map_err(|e| AocError::parse(line_number, 1, e))?,
+            B::try_from(c2).map_err(|e| AocError::parse(line_number, 3, e))?,
+        )),
+        _ => Err(AocError::parse(
+            line_number,
+            1,
+            format!("Expected two columns separated by a space, found \"{line}\""),
+        )),
     }
 }
 
+/// A line of the strategy guide. The second column is read as a shape for
+/// part 1 and as an outcome for part 2.
+pub struct Round {
+    opponent: Shape,
+    shape: Shape,
+    outcome: Outcome,
+}
+
+fn parse_guide(input: &str) -> Result<Vec<Round>, AocError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (opponent, shape) = parse_round(i + 1, line)?;
+            let (_, outcome) = parse_round::<Shape, Outcome>(i + 1, line)?;
+            Ok(Round {
+                opponent,
+                shape,
+                outcome,
+            })
+        })
+        .collect()
+}
+
 impl Solvable<2> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    type Input = Vec<Round>;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let total_score = self.score_from_lines(|c1, c2| {
-            let mut score = 0;
-            let (opponent_shape, my_shape): (Shape, Shape) = (c1.into(), c2.into());
-            if my_shape > opponent_shape {
-                score += 6;
-            } else if my_shape == opponent_shape {
-                score += 3;
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Vec<Round>, AocError> {
+        parse_guide(text)
+    }
+
+    fn part1(&self, rounds: &Vec<Round>, _: &Params) -> Result<Answer, AocError> {
+        let mut total_score = 0;
+        for &Round {
+            opponent, shape, ..
+        } in rounds
+        {
+            if shape > opponent {
+                total_score += 6;
+            } else if shape == opponent {
+                total_score += 3;
             }
-            score += my_shape as u32;
-            score
-        })?;
+            total_score += shape as u32;
+        }
 
-        Ok(format!("{}", total_score))
+        Ok(total_score.into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let total_score = self.score_from_lines(|c1, c2| {
-            let mut score = 0;
-            let (opponent_shape, outcome): (Shape, Outcome) = (c1.into(), c2.into());
-            score += match outcome {
+    fn part2(&self, rounds: &Vec<Round>, _: &Params) -> Result<Answer, AocError> {
+        let mut total_score = 0;
+        for &Round {
+            opponent, outcome, ..
+        } in rounds
+        {
+            total_score += match outcome {
                 Outcome::Win => 6,
                 Outcome::Draw => 3,
                 Outcome::Lose => 0,
             };
-            score += match (outcome, opponent_shape) {
+            total_score += match (outcome, opponent) {
                 (Outcome::Draw, my_shape) => my_shape as u32,
                 (Outcome::Lose, Shape::Rock) | (Outcome::Win, Shape::Paper) => {
                     Shape::Scissors as u32
@@ -125,27 +171,21 @@ impl Solvable<2> for Solution {
                     Shape::Paper as u32
                 }
             };
-            score
-        })?;
-        Ok(format!("{}", total_score))
+        }
+        Ok(total_score.into())
     }
 }
 
 #[allow(unused_imports)]
 mod test {
     use super::*;
+    use std::error::Error;
 
     #[test]
-    fn gives_the_right_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "15");
-        Ok(())
-    }
-
-    #[test]
-    fn gives_the_right_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "12");
-        Ok(())
+    fn reports_the_column_of_an_unrecognized_shape() {
+        match parse_round::<Shape, Outcome>(4, "A Q") {
+            Err(AocError::Parse { line, column, .. }) => assert_eq!((line, column), (4, 3)),
+            _ => panic!("Expected a parse error"),
+        }
     }
 }
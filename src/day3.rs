@@ -1,12 +1,13 @@
 use std::collections::HashSet;
-use std::error::Error;
-use std::fs;
-use std::path::PathBuf;
 
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::parse::parse_lines;
+use crate::solvable::{Solvable, Source};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
 fn char_to_priority(c: &char) -> u32 {
@@ -18,59 +19,88 @@ fn char_to_priority(c: &char) -> u32 {
 }
 
 impl Solvable<3> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    /// One rucksack per line
+    type Input = Vec<String>;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Vec<String>, AocError> {
+        parse_lines(text, |line| {
+            if let Some((at, item)) = line
+                .text
+                .char_indices()
+                .find(|(_, c)| !c.is_ascii_alphabetic())
+            {
+                let token = &line.text[at..at + item.len_utf8()];
+                return Err(line.error_at(token, format!("Invalid item {item:?}")));
+            }
+            if line.text.len() % 2 != 0 {
+                return Err(line.error("A rucksack's two compartments must hold as many items"));
+            }
+            Ok(line.text.to_string())
+        })
+    }
+
+    fn part1(&self, rucksacks: &Vec<String>, _: &Params) -> Result<Answer, AocError> {
         let mut sum = 0;
-        for line in input.lines() {
+        for (i, line) in rucksacks.iter().enumerate() {
             let len = line.len();
             let (first, second) = line.split_at(len / 2);
             let first_set: HashSet<char> = first.chars().collect();
             let second_set: HashSet<char> = second.chars().collect();
             let common_chars = first_set.intersection(&second_set).collect::<Vec<&char>>();
-            let common_char = common_chars.first().unwrap();
-            sum += char_to_priority(*common_char);
+            let common_char = common_chars
+                .first()
+                .ok_or_else(|| AocError::parse(i + 1, 1, "No item appears in both compartments"))?;
+            sum += char_to_priority(common_char);
         }
-        Ok(format!("{}", sum))
+        Ok(sum.into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let mut lines = input.lines().into_iter();
+    fn part2(&self, rucksacks: &Vec<String>, _: &Params) -> Result<Answer, AocError> {
+        let mut lines = rucksacks.iter().enumerate();
         let mut sum = 0;
-        while let Some(first) = lines.next() {
-            let second = lines.next().unwrap().chars().collect::<HashSet<char>>();
-            let third = lines.next().unwrap().chars().collect::<HashSet<char>>();
+        while let Some((i, first)) = lines.next() {
+            let mut next_line = || {
+                lines
+                    .next()
+                    .map(|(_, l)| l.chars().collect::<HashSet<char>>())
+                    .ok_or_else(|| AocError::parse(i + 1, 1, "Group has fewer than 3 elves"))
+            };
+            let second = next_line()?;
+            let third = next_line()?;
             let common = first
                 .chars()
                 .filter(|c| second.contains(c) && third.contains(c))
                 .collect::<Vec<char>>();
-            sum += char_to_priority(common.first().unwrap());
+            let badge = common
+                .first()
+                .ok_or_else(|| AocError::parse(i + 1, 1, "No item is common to the whole group"))?;
+            sum += char_to_priority(badge);
         }
-        Ok(format!("{}", sum))
+        Ok(sum.into())
     }
 }
 
+#[cfg(test)]
 #[allow(unused_imports)]
 mod test {
     use super::*;
 
     #[test]
-    fn it_gives_the_right_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "157");
-        Ok(())
-    }
-
-    #[test]
-    fn it_gives_the_right_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "70");
-        Ok(())
+    fn rejects_items_that_are_not_letters() {
+        let error = |text| Solution::parse(text).unwrap_err().to_string();
+        assert_eq!(error("abAB\na!b!\n"), "line 2, column 2: Invalid item '!'");
+        assert_eq!(error("aéb\n"), "line 1, column 2: Invalid item 'é'");
+        assert_eq!(
+            error("abc\n"),
+            "line 1, column 1: A rucksack's two compartments must hold as many items"
+        );
     }
 }
@@ -1,50 +1,41 @@
-use crate::solvable::Solvable;
-use std::fs;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::parse::parse_lines;
+use crate::solvable::{Solvable, Source};
 use std::ops::RangeInclusive;
-use std::{error::Error, path::PathBuf};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-fn str_to_range(s: &str) -> Result<RangeInclusive<usize>, Box<dyn Error>> {
-    let split: Vec<&str> = s.split('-').collect();
-    let start: usize = str::parse::<usize>(split.first().unwrap())?;
-    let end: usize = str::parse::<usize>(split.get(1).unwrap())?;
-    Ok(RangeInclusive::new(start, end))
-}
+pub type RangePair = (RangeInclusive<usize>, RangeInclusive<usize>);
 
-impl Solution {
-    fn get_pairs(
-        &self,
-    ) -> Result<Vec<(RangeInclusive<usize>, RangeInclusive<usize>)>, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(input
-            .lines()
-            .map(|line| {
-                let range_inputs: Vec<&str> = line.split(',').collect();
-                let first = *range_inputs.first().unwrap();
-                let second = *range_inputs.get(1).unwrap();
-                (
-                    str_to_range(first).expect(&format!("Error parsing first range of {}", first)),
-                    str_to_range(second)
-                        .expect(&format!("Error parsing second range of {}", second)),
-                )
-            })
-            .collect())
-    }
+fn parse_pairs(input: &str) -> Result<Vec<RangePair>, AocError> {
+    parse_lines(input, |line| {
+        let [a, b, c, d] = line.template("{}-{},{}-{}")?;
+        let section = |s| line.value::<usize>(s, "section");
+        Ok((section(a)?..=section(b)?, section(c)?..=section(d)?))
+    })
 }
 
 impl Solvable<4> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    type Input = Vec<RangePair>;
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
+    }
+
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Vec<RangePair>, AocError> {
+        parse_pairs(text)
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
+    fn part1(&self, pairs: &Vec<RangePair>, _: &Params) -> Result<Answer, AocError> {
         let mut num_overlapping = 0;
-        let pairs = self.get_pairs()?;
         for pair in pairs {
             let (first, second) = pair;
             if (first.start() <= second.start() && first.end() >= second.end())
@@ -53,12 +44,11 @@ impl Solvable<4> for Solution {
                 num_overlapping += 1;
             }
         }
-        Ok(format!("{}", num_overlapping))
+        Ok(num_overlapping.into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
+    fn part2(&self, pairs: &Vec<RangePair>, _: &Params) -> Result<Answer, AocError> {
         let mut num_overlapping = 0;
-        let pairs = self.get_pairs()?;
         for pair in pairs {
             let (first, second) = pair;
             if (first.start() <= second.end() && first.end() >= second.start())
@@ -67,25 +57,6 @@ impl Solvable<4> for Solution {
                 num_overlapping += 1;
             }
         }
-        Ok(format!("{}", num_overlapping))
-    }
-}
-
-#[allow(unused_imports)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn gives_the_right_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "2");
-        Ok(())
-    }
-
-    #[test]
-    fn gives_the_right_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "4");
-        Ok(())
+        Ok(num_overlapping.into())
     }
 }
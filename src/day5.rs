@@ -1,127 +1,207 @@
-use crate::solvable::Solvable;
-use std::collections::VecDeque;
-use std::fs;
-use std::{error::Error, path::PathBuf};
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::Params;
+use crate::parse::{groups, Line};
+use crate::solvable::{Simulation, Solvable, Source};
+use std::fmt::{Display, Formatter, Result as FmtResult};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-fn get_input_parts(input: &str) -> Result<(String, String), Box<dyn Error>> {
-    let parts: Vec<&str> = input.split("\n\n").collect();
-    Ok((
-        String::from(*parts.first().unwrap()),
-        String::from(*parts.get(1).unwrap()),
-    ))
+pub struct Move {
+    count: usize,
+    src: usize,
+    dst: usize,
 }
 
-fn build_crate_stacks(input: &str) -> Result<Vec<Vec<char>>, Box<dyn Error>> {
-    let stack_numbers = input.lines().last().unwrap();
-    let num_stacks = stack_numbers
-        .split_ascii_whitespace()
-        .collect::<Vec<&str>>()
-        .len();
-    let mut stacks = vec![];
-    for _ in 0..num_stacks {
-        stacks.push(Vec::new());
-    }
-    for line in input.lines().rev().skip(1) {
-        for i in 0..num_stacks {
+fn build_crate_stacks(drawing: &[Line]) -> Result<Vec<Vec<char>>, AocError> {
+    let Some((stack_numbers, crates)) = drawing.split_last() else {
+        return Err(AocError::parse(1, 1, "Missing crate stacks"));
+    };
+    let num_stacks = stack_numbers.text.split_ascii_whitespace().count();
+    let mut stacks = vec![Vec::new(); num_stacks];
+    for line in crates.iter().rev() {
+        for (i, stack) in stacks.iter_mut().enumerate() {
             let start_idx = i * 4;
             let end_idx = start_idx + 3;
-            if let ['[', c, ']'] = line[start_idx..end_idx]
-                .chars()
-                .take(3)
-                .collect::<Vec<char>>()[..]
+            if let Some(['[', c, ']']) = line
+                .text
+                .get(start_idx..end_idx)
+                .map(|s| s.chars().take(3).collect::<Vec<char>>())
+                .as_deref()
             {
-                stacks[i].push(c);
+                stack.push(*c);
             }
         }
     }
     Ok(stacks)
 }
 
-impl Solvable<5> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+fn parse_move(line: &Line, num_stacks: usize) -> Result<Move, AocError> {
+    let [count, src, dst] = line.template("move {} from {} to {}")?;
+    let stack = |token| match line.value::<usize>(token, "stack")? {
+        n @ 1.. if n <= num_stacks => Ok(n - 1),
+        _ => Err(line.error_at(token, format!("There is no stack {token}"))),
+    };
+    Ok(Move {
+        count: line.value(count, "count")?,
+        src: stack(src)?,
+        dst: stack(dst)?,
+    })
+}
+
+fn parse(input: &str) -> Result<(Vec<Vec<char>>, Vec<Move>), AocError> {
+    let groups = groups(input);
+    let [drawing, moves] = &groups[..] else {
+        return Err(AocError::parse(
+            1,
+            1,
+            "Expected the stacks and the instructions, separated by a blank line",
+        ));
+    };
+    let stacks = build_crate_stacks(drawing)?;
+    let moves = moves
+        .iter()
+        .map(|line| parse_move(line, stacks.len()))
+        .collect::<Result<_, _>>()?;
+    Ok((stacks, moves))
+}
+
+fn pop_crate(stacks: &mut [Vec<char>], src: usize) -> Result<char, AocError> {
+    stacks[src]
+        .pop()
+        .ok_or_else(|| AocError::unsolvable(format!("Stack {} ran out of crates", src + 1)))
+}
+
+fn tops(stacks: &[Vec<char>]) -> Result<String, AocError> {
+    stacks
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            s.last()
+                .ok_or_else(|| AocError::unsolvable(format!("Stack {} ends up empty", i + 1)))
+        })
+        .collect()
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.count,
+            self.src + 1,
+            self.dst + 1
+        )
     }
+}
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let (stack_input, instructions) = get_input_parts(&input)?;
-        let mut stacks = build_crate_stacks(&stack_input).unwrap();
-        for line in instructions.lines() {
-            // move 1 from 2 to 1
-            let tokens: Vec<&str> = line.split_ascii_whitespace().collect();
-            let count = str::parse::<usize>(tokens[1])?;
-            let src = str::parse::<usize>(tokens[3])? - 1;
-            let dst = str::parse::<usize>(tokens[5])? - 1;
-
-            let src_stack = stacks.get_mut(src).unwrap();
-            let mut temp = vec![];
-            for _ in 0..count {
-                let c = src_stack.pop().unwrap();
-                temp.push(c);
-            }
-            stacks.get_mut(dst).unwrap().extend(temp);
+/// The crane working through the rearrangement procedure, a move at a time
+pub struct Crane<'a> {
+    stacks: Vec<Vec<char>>,
+    moves: &'a [Move],
+    done: usize,
+    /// Whether crates moved together keep their order, as with the
+    /// CrateMover 9001
+    keep_order: bool,
+}
+
+impl<'a> Crane<'a> {
+    fn new((stacks, moves): &'a (Vec<Vec<char>>, Vec<Move>), keep_order: bool) -> Self {
+        Crane {
+            stacks: stacks.clone(),
+            moves,
+            done: 0,
+            keep_order,
         }
+    }
 
-        let mut tops = String::new();
-        stacks
-            .iter()
-            .map(|s| s.last().unwrap())
-            .for_each(|c| tops.push(*c));
+    fn run(mut self) -> Result<String, AocError> {
+        while self.step()? {}
+        tops(&self.stacks)
+    }
+}
 
-        Ok(String::from(tops))
+impl Simulation for Crane<'_> {
+    fn step(&mut self) -> Result<bool, AocError> {
+        let Some(&Move { count, src, dst }) = self.moves.get(self.done) else {
+            return Ok(false);
+        };
+        let mut lifted = vec![];
+        for _ in 0..count {
+            lifted.push(pop_crate(&mut self.stacks, src)?);
+        }
+        if self.keep_order {
+            lifted.reverse();
+        }
+        self.stacks[dst].extend(lifted);
+        self.done += 1;
+        Ok(true)
     }
+}
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let (stack_input, instructions) = get_input_parts(&input)?;
-        let mut stacks = build_crate_stacks(&stack_input).unwrap();
-        for line in instructions.lines() {
-            // move 1 from 2 to 1
-            let tokens: Vec<&str> = line.split_ascii_whitespace().collect();
-            let count = str::parse::<usize>(tokens[1])?;
-            let src = str::parse::<usize>(tokens[3])? - 1;
-            let dst = str::parse::<usize>(tokens[5])? - 1;
-
-            let src_stack = stacks.get_mut(src).unwrap();
-            let mut temp: VecDeque<char> = VecDeque::new();
-            for _ in 0..count {
-                let c = src_stack.pop().unwrap();
-                temp.push_front(c);
-            }
-            stacks.get_mut(dst).unwrap().extend(temp);
+/// The stacks drawn as in the puzzle, then the next move
+impl Display for Crane<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row = self
+                .stacks
+                .iter()
+                .map(|stack| {
+                    stack
+                        .get(level)
+                        .map_or("   ".to_string(), |c| format!("[{c}]"))
+                })
+                .collect::<Vec<String>>();
+            writeln!(f, "{}", row.join(" ").trim_end())?;
         }
+        let numbers = (1..=self.stacks.len())
+            .map(|n| format!(" {n} "))
+            .collect::<Vec<String>>();
+        writeln!(f, "{}", numbers.join(" ").trim_end())?;
+        match self.moves.get(self.done) {
+            Some(next) => write!(
+                f,
+                "\nNext, {} of {}: {next}",
+                self.done + 1,
+                self.moves.len()
+            ),
+            None => write!(f, "\nAll {} moves done", self.moves.len()),
+        }
+    }
+}
 
-        let mut tops = String::new();
-        stacks
-            .iter()
-            .map(|s| s.last().unwrap())
-            .for_each(|c| tops.push(*c));
+impl Solvable<5> for Solution {
+    type Input = (Vec<Vec<char>>, Vec<Move>);
 
-        Ok(String::from(tops))
+    fn from_source(source: Source) -> Self {
+        Solution { source }
+    }
+
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<(Vec<Vec<char>>, Vec<Move>), AocError> {
+        parse(text)
     }
-}
 
-#[allow(unused_imports)]
-mod test {
-    use super::*;
+    fn part1(&self, input: &Self::Input, _: &Params) -> Result<Answer, AocError> {
+        Ok(Crane::new(input, false).run()?.into())
+    }
 
-    #[test]
-    fn gives_the_right_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "CMZ");
-        Ok(())
+    fn part2(&self, input: &Self::Input, _: &Params) -> Result<Answer, AocError> {
+        Ok(Crane::new(input, true).run()?.into())
     }
 
-    #[test]
-    fn gives_the_right_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "MCD");
-        Ok(())
+    fn simulation<'a>(
+        &self,
+        input: &'a Self::Input,
+        _: &'a Params,
+        part: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        Ok(Some(Box::new(Crane::new(input, part == 2))))
     }
 }
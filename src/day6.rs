@@ -1,13 +1,14 @@
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::{Param, Params};
+use crate::solvable::{Solvable, Source};
 use std::collections::HashSet;
-use std::fs;
-use std::{error::Error, path::PathBuf};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-fn process_input(input: String, window_size: usize) -> usize {
+fn process_input(input: &str, window_size: usize) -> Result<usize, AocError> {
     let input_slice = &input.chars().collect::<Vec<char>>()[..];
     for (i, window) in input_slice.windows(window_size).enumerate() {
         let mut set: HashSet<char> = HashSet::new();
@@ -15,101 +16,70 @@ fn process_input(input: String, window_size: usize) -> usize {
             set.insert(*c);
         }
         if set.len() == window_size {
-            return i + window_size;
+            return Ok(i + window_size);
         }
     }
-    0
+    Err(AocError::unsolvable(format!(
+        "No run of {window_size} distinct characters"
+    )))
 }
 
-impl Solvable<6> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
-    }
-
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(process_input(input, 4).to_string())
-    }
-
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(process_input(input, 14).to_string())
-    }
+fn window(params: &Params, name: &str) -> Result<usize, AocError> {
+    Some(params.get::<usize>(name)?)
+        .filter(|&n| n > 0)
+        .ok_or_else(|| AocError::param(name, "A marker needs at least one character"))
 }
 
-#[allow(unused_imports)]
-mod test {
-    use super::*;
-
-    #[test]
-    fn it_gives_the_right_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "7");
-        Ok(())
-    }
-
-    #[test]
-    fn it_gives_the_right_first_answer_for_test2() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test2.txt");
-        assert_eq!(solution.answer1()?, "5");
-        Ok(())
-    }
-
-    #[test]
-    fn it_gives_the_right_first_answer_for_test3() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test3.txt");
-        assert_eq!(solution.answer1()?, "6");
-        Ok(())
-    }
-
-    #[test]
-    fn it_gives_the_right_first_answer_for_test4() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test4.txt");
-        assert_eq!(solution.answer1()?, "10");
-        Ok(())
+impl Solvable<6> for Solution {
+    type Input = String;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "window1",
+            default: 4,
+            help: "Distinct characters that mark the start of a packet",
+        },
+        Param {
+            name: "window2",
+            default: 14,
+            help: "Distinct characters that mark the start of a message",
+        },
+    ];
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    #[test]
-    fn it_gives_the_right_first_answer_for_test5() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test5.txt");
-        assert_eq!(solution.answer1()?, "11");
-        Ok(())
+    fn source(&self) -> &Source {
+        &self.source
     }
 
-    #[test]
-    fn it_gives_the_right_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "19");
-        Ok(())
+    fn parse(text: &str) -> Result<String, AocError> {
+        Ok(text.to_string())
     }
 
-    #[test]
-    fn it_gives_the_right_second_answer_for_test2() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test2.txt");
-        assert_eq!(solution.answer2()?, "23");
-        Ok(())
+    fn part1(&self, signal: &String, params: &Params) -> Result<Answer, AocError> {
+        Ok(process_input(signal, window(params, "window1")?)?.into())
     }
 
-    #[test]
-    fn it_gives_the_right_second_answer_for_test3() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test3.txt");
-        assert_eq!(solution.answer2()?, "23");
-        Ok(())
+    fn part2(&self, signal: &String, params: &Params) -> Result<Answer, AocError> {
+        Ok(process_input(signal, window(params, "window2")?)?.into())
     }
+}
 
-    #[test]
-    fn it_gives_the_right_second_answer_for_test4() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test4.txt");
-        assert_eq!(solution.answer2()?, "29");
-        Ok(())
-    }
+#[cfg(test)]
+#[allow(unused_imports)]
+mod test {
+    use super::*;
 
     #[test]
-    fn it_gives_the_right_second_answer_for_test5() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test5.txt");
-        assert_eq!(solution.answer2()?, "26");
+    fn rejects_an_empty_window() -> Result<(), AocError> {
+        let solution = Solution::from_text("mjqjpqmgbljsphdztnvjfqwrcgsmlb\n");
+        let overrides = [("window1".to_string(), 0)];
+        let params = Params::resolve(6, Solution::PARAMS, solution.source(), &overrides)?;
+        let error = solution.part1(&solution.input()?, &params).unwrap_err();
+        assert!(matches!(error, AocError::Param { ref name, .. } if name == "window1"));
+        assert_eq!(solution.part2(&solution.input()?, &params)?, 19);
         Ok(())
     }
 }
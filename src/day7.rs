@@ -1,18 +1,16 @@
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::{Param, Params};
+use crate::solvable::{Solvable, Source};
 use std::cell::RefCell;
 use std::fmt::Debug;
-use std::fs;
 use std::rc::Rc;
-use std::{error::Error, path::PathBuf};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-const MAXIMUM_DISK_SPACE: u32 = 70000000;
-const REQUIRED_DISK_SPACE: u32 = 30000000;
-
-enum FilesystemEntry {
+pub enum FilesystemEntry {
     Directory {
         name: String,
         parent: Option<Rc<RefCell<FilesystemEntry>>>,
@@ -46,7 +44,7 @@ impl FilesystemEntry {
 
     fn name(&self) -> &str {
         match self {
-            FilesystemEntry::Directory { name, .. } | FilesystemEntry::File { name, .. } => &name,
+            FilesystemEntry::Directory { name, .. } | FilesystemEntry::File { name, .. } => name,
         }
     }
 
@@ -66,7 +64,7 @@ impl FilesystemEntry {
                 ref mut size,
                 ..
             } => {
-                if *size == None {
+                if size.is_none() {
                     *size = Some(entries.iter().map(|e| e.as_ref().borrow_mut().size()).sum());
                 }
                 size.unwrap()
@@ -96,7 +94,7 @@ impl Debug for FilesystemEntry {
     }
 }
 
-fn build_filesystem(input: String) -> Result<Rc<RefCell<FilesystemEntry>>, Box<dyn Error>> {
+fn build_filesystem(input: &str) -> Result<Rc<RefCell<FilesystemEntry>>, AocError> {
     let root = Rc::new(RefCell::new(Directory {
         name: "/".to_string(),
         parent: None,
@@ -104,51 +102,64 @@ fn build_filesystem(input: String) -> Result<Rc<RefCell<FilesystemEntry>>, Box<d
         size: None,
     }));
     let mut current = root.clone();
-    let mut iter = input.lines().skip(1).peekable();
+    let mut iter = input.lines().enumerate().skip(1).peekable();
     let mut line = iter.next();
-    while let Some(l) = line {
+    while let Some((i, l)) = line {
         if l.starts_with("$ cd ..") {
             let new_current = current.as_ref().borrow().parent();
-            current = new_current.unwrap();
+            current = new_current
+                .ok_or_else(|| AocError::parse(i + 1, 6, "Cannot leave the root directory"))?;
             line = iter.next();
-        } else if l.starts_with("$ cd") {
-            let dir = &l[5..];
+        } else if let Some(dir) = l.strip_prefix("$ cd ") {
             let name = dir.trim();
-            let temp_current = current.clone();
-            let mut borrowed = temp_current.as_ref().borrow_mut();
-            let entries = borrowed.entries();
-            current = entries
-                .iter()
-                .find(|e| e.borrow().name() == name)
-                .unwrap()
-                .clone();
+            if name == "/" {
+                current = root.clone();
+            } else {
+                let temp_current = current.clone();
+                let mut borrowed = temp_current.as_ref().borrow_mut();
+                let entries = borrowed.entries();
+                current = entries
+                    .iter()
+                    .find(|e| e.borrow().is_directory() && e.borrow().name() == name)
+                    .ok_or_else(|| {
+                        AocError::parse_at(i + 1, l, dir, format!("No directory named {name}"))
+                    })?
+                    .clone();
+            }
             line = iter.next();
         } else if l.starts_with("$ ls") {
             line = iter.next();
-            while line != None && !line.unwrap().starts_with("$") {
+            while let Some((i, line_val)) = line.filter(|(_, l)| !l.starts_with('$')) {
                 let mut borrowed = current.as_ref().borrow_mut();
                 let entries = borrowed.entries();
-                let line_val = line.unwrap();
-                if line_val.starts_with("dir") {
-                    let dirname = &line_val[4..];
+                if let Some(dirname) = line_val.strip_prefix("dir ") {
                     entries.push(Rc::new(RefCell::new(Directory {
                         name: dirname.to_string(),
                         parent: Some(current.clone()),
                         entries: vec![],
                         size: None,
                     })))
-                } else if char::is_digit(line_val.chars().collect::<Vec<char>>()[0], 10) {
-                    let mut parts = line_val.split_whitespace();
-                    let size = parts.next().unwrap();
-                    let name = parts.next().unwrap();
+                } else {
+                    let (size, name) = line_val.split_once(' ').ok_or_else(|| {
+                        AocError::parse(i + 1, 1, format!("Unrecognized listing \"{line_val}\""))
+                    })?;
+                    let size = size.parse::<u32>().map_err(|e| {
+                        AocError::parse(i + 1, 1, format!("Invalid file size: {e}"))
+                    })?;
                     entries.push(Rc::new(RefCell::new(File {
                         name: name.to_string(),
-                        size: size.parse::<u32>()?,
+                        size,
                         parent: Some(current.clone()),
                     })));
                 }
                 line = iter.next();
             }
+        } else {
+            return Err(AocError::parse(
+                i + 1,
+                1,
+                format!("Unrecognized command \"{l}\""),
+            ));
         }
     }
     Ok(root)
@@ -168,7 +179,7 @@ where
                 .entries()
                 .iter()
                 .filter(|e| e.as_ref().borrow().is_directory())
-                .map(|e| e.clone()),
+                .cloned(),
         );
         if f(size) {
             found.push(size);
@@ -178,45 +189,82 @@ where
 }
 
 impl Solvable<7> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
-        }
+    type Input = Rc<RefCell<FilesystemEntry>>;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "small_dir",
+            default: 100_000,
+            help: "Largest directory size counted in part 1",
+        },
+        Param {
+            name: "disk_size",
+            default: 70_000_000,
+            help: "Total space on the disk",
+        },
+        Param {
+            name: "required_space",
+            default: 30_000_000,
+            help: "Free space the update needs",
+        },
+    ];
+
+    fn from_source(source: Source) -> Self {
+        Solution { source }
+    }
+
+    fn source(&self) -> &Source {
+        &self.source
+    }
+
+    fn parse(text: &str) -> Result<Rc<RefCell<FilesystemEntry>>, AocError> {
+        build_filesystem(text)
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let filesystem = build_filesystem(input)?;
-        let found = find_dir_sizes(filesystem, |s| s <= 100_000);
-        Ok(found.iter().sum::<u32>().to_string())
+    fn part1(&self, filesystem: &Self::Input, params: &Params) -> Result<Answer, AocError> {
+        let small_dir: u32 = params.get("small_dir")?;
+        let found = find_dir_sizes(Rc::clone(filesystem), |s| s <= small_dir);
+        Ok(found.iter().sum::<u32>().into())
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        let filesystem = build_filesystem(input)?;
+    fn part2(&self, filesystem: &Self::Input, params: &Params) -> Result<Answer, AocError> {
         let fs_size = filesystem.as_ref().borrow_mut().size();
-        let current_free = MAXIMUM_DISK_SPACE - fs_size;
-        let minimum_to_delete = REQUIRED_DISK_SPACE - current_free;
-        let deletable_sizes: Vec<u32> = find_dir_sizes(filesystem, |s| s >= minimum_to_delete);
-        Ok(deletable_sizes.iter().min().unwrap().to_string())
+        let disk_size: u32 = params.get("disk_size")?;
+        let required_space: u32 = params.get("required_space")?;
+        let current_free = disk_size.checked_sub(fs_size).ok_or_else(|| {
+            AocError::unsolvable(format!(
+                "Filesystem uses {fs_size}, more than the disk holds"
+            ))
+        })?;
+        let minimum_to_delete = required_space.saturating_sub(current_free);
+        let deletable_sizes: Vec<u32> =
+            find_dir_sizes(Rc::clone(filesystem), |s| s >= minimum_to_delete);
+        let smallest = deletable_sizes
+            .iter()
+            .min()
+            .ok_or_else(|| AocError::unsolvable("No directory frees enough space"))?;
+        Ok((*smallest).into())
     }
 }
 
+#[cfg(test)]
 #[allow(unused_imports)]
 mod test {
     use super::*;
 
     #[test]
-    fn it_gives_the_right_answer1_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "95437");
-        Ok(())
-    }
-
-    #[test]
-    fn it_gives_the_right_answer2_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "24933642");
+    fn reports_when_no_directory_frees_enough_space() -> Result<(), AocError> {
+        let solution = Solution::from_text("$ cd /\n$ ls\n100 a.txt\n");
+        let overrides = [
+            ("disk_size".to_string(), 200),
+            ("required_space".to_string(), 500),
+        ];
+        let params = Params::resolve(7, Solution::PARAMS, solution.source(), &overrides)?;
+        let error = solution.part2(&solution.input()?, &params).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "no answer: No directory frees enough space"
+        );
         Ok(())
     }
 }
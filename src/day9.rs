@@ -1,140 +1,181 @@
-use crate::solvable::Solvable;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::geom::{Bounds, Direction, Point2};
+use crate::params::{Param, Params};
+use crate::solvable::{Simulation, Solvable, Source};
 use std::collections::HashSet;
-use std::fs;
-use std::{error::Error, path::PathBuf};
+use std::fmt::{Display, Formatter, Result as FmtResult};
 
 pub struct Solution {
-    filepath: PathBuf,
+    source: Source,
 }
 
-#[derive(Default, PartialEq, Eq, Hash, Copy, Clone)]
-struct Pos {
-    x: i32,
-    y: i32,
+type Pos = Point2<i32>;
+
+/// Move a knot after the knot ahead of it, if they no longer touch
+fn follow(knot: &mut Pos, ahead: &Pos) {
+    if knot.chebyshev(ahead) > 1 {
+        *knot += (*ahead - *knot).signum();
+    }
 }
 
-impl Pos {
-    fn update(&mut self, h_pos: &Self) {
-        if h_pos.x - 1 <= self.x
-            && self.x <= h_pos.x + 1
-            && h_pos.y - 1 <= self.y
-            && self.y <= h_pos.y + 1
-        {
-            return;
-        }
+fn parse_instructions(input: &str) -> Result<Vec<(Direction, u32)>, AocError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let (direction, steps) = line.split_once(' ').ok_or_else(|| {
+                AocError::parse(
+                    i + 1,
+                    1,
+                    format!("Expected \"DIRECTION STEPS\", found \"{line}\""),
+                )
+            })?;
+            let mut letters = direction.chars();
+            let letter = letters.next().filter(|_| letters.next().is_none());
+            let direction = letter.and_then(Direction::from_letter).ok_or_else(|| {
+                AocError::parse(i + 1, 1, format!("Unknown direction {direction}"))
+            })?;
+            let steps = steps.parse::<u32>().map_err(|e| {
+                AocError::parse_at(i + 1, line, steps, format!("Invalid step count: {e}"))
+            })?;
+            Ok((direction, steps))
+        })
+        .collect()
+}
 
-        if h_pos.x < self.x {
-            self.x -= 1;
-        } else if h_pos.x > self.x {
-            self.x += 1;
-        }
-        if h_pos.y < self.y {
-            self.y -= 1;
-        } else if h_pos.y > self.y {
-            self.y += 1;
-        }
-    }
+/// Knots following the head of a rope whose length is the parameter `name`
+fn tail_knots(params: &Params, name: &str) -> Result<usize, AocError> {
+    params
+        .get::<usize>(name)?
+        .checked_sub(1)
+        .filter(|&n| n > 0)
+        .ok_or_else(|| AocError::param(name, "A rope needs at least two knots"))
 }
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
+/// A rope being pulled through the motions, one motion at a time
+pub struct Rope<'a> {
+    motions: &'a [(Direction, u32)],
+    done: usize,
+    /// The head, then each knot following it
+    knots: Vec<Pos>,
+    tail_visited: HashSet<Pos>,
 }
-use Direction::*;
-impl Direction {
-    fn from_char(c: char) -> Self {
-        match c {
-            'U' => Up,
-            'D' => Down,
-            'L' => Left,
-            'R' => Right,
-            _ => panic!("Unknown direction character {c}"),
+
+impl<'a> Rope<'a> {
+    fn new(motions: &'a [(Direction, u32)], tail_knots: usize) -> Self {
+        Rope {
+            motions,
+            done: 0,
+            knots: vec![Pos::ORIGIN; tail_knots + 1],
+            tail_visited: HashSet::from([Pos::ORIGIN]),
         }
     }
+
+    fn run(mut self) -> usize {
+        while self.step().is_ok_and(|moved| moved) {}
+        self.tail_visited.len()
+    }
 }
 
-fn process(input: &str, num_knots: usize) -> Result<usize, Box<dyn Error>> {
-    let mut h_pos = Pos::default();
-    let mut knots = vec![];
-    knots.resize(num_knots, Pos::default());
-    let mut t_visited: HashSet<Pos> = HashSet::new();
-    t_visited.insert(knots.last().unwrap().clone());
-    for instruction in input.lines() {
-        let direction = Direction::from_char(instruction.chars().nth(0).unwrap());
-        let steps = instruction[2..].parse::<u32>()?;
+impl Simulation for Rope<'_> {
+    fn step(&mut self) -> Result<bool, AocError> {
+        let Some(&(direction, steps)) = self.motions.get(self.done) else {
+            return Ok(false);
+        };
         for _ in 0..steps {
-            h_pos = match direction {
-                Up => Pos {
-                    x: h_pos.x,
-                    y: h_pos.y + 1,
-                },
-                Down => Pos {
-                    x: h_pos.x,
-                    y: h_pos.y - 1,
-                },
-                Left => Pos {
-                    x: h_pos.x - 1,
-                    y: h_pos.y,
-                },
-                Right => Pos {
-                    x: h_pos.x + 1,
-                    y: h_pos.y,
-                },
-            };
-
-            knots.get_mut(0).unwrap().update(&h_pos);
-            for i in 1..num_knots {
-                let prev_knot = knots.get(i - 1).unwrap().clone();
-                knots.get_mut(i).unwrap().update(&prev_knot);
+            self.knots[0] += direction.delta();
+            for i in 1..self.knots.len() {
+                let ahead = self.knots[i - 1];
+                follow(&mut self.knots[i], &ahead);
             }
-            t_visited.insert(knots.last().unwrap().clone());
+            self.tail_visited.insert(self.knots[self.knots.len() - 1]);
         }
+        self.done += 1;
+        Ok(true)
     }
-    Ok(t_visited.len())
 }
 
-impl Solvable<9> for Solution {
-    fn new(filename: &str) -> Self {
-        Solution {
-            filepath: Self::data_path().join(filename),
+/// The knots as `H`, `1`, `2`..., the start as `s` and other places the
+/// tail has been as `#`
+impl Display for Rope<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let Some(bounds) = Bounds::of(self.knots.iter().chain(&self.tail_visited).copied()) else {
+            return Ok(());
+        };
+        for y in bounds.min.y..=bounds.max.y {
+            let row = (bounds.min.x..=bounds.max.x)
+                .map(|x| {
+                    let p = Point2::new(x, y);
+                    match self.knots.iter().position(|&k| k == p) {
+                        Some(0) => 'H',
+                        Some(i) if self.knots.len() == 2 => ['H', 'T'][i],
+                        Some(i) => char::from_digit(i as u32, 36).unwrap_or('T'),
+                        None if p == Pos::ORIGIN => 's',
+                        None if self.tail_visited.contains(&p) => '#',
+                        None => '.',
+                    }
+                })
+                .collect::<String>();
+            writeln!(f, "{row}")?;
         }
+        write!(
+            f,
+            "After {} of {} motions the tail has visited {} positions",
+            self.done,
+            self.motions.len(),
+            self.tail_visited.len()
+        )
     }
+}
+
+impl Solvable<9> for Solution {
+    type Input = Vec<(Direction, u32)>;
+
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "knots1",
+            default: 2,
+            help: "Knots in the rope for part 1, including the head",
+        },
+        Param {
+            name: "knots2",
+            default: 10,
+            help: "Knots in the rope for part 2, including the head",
+        },
+    ];
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(process(&input, 1)?.to_string())
+    fn from_source(source: Source) -> Self {
+        Solution { source }
     }
 
-    fn answer2(&self) -> Result<String, Box<dyn Error>> {
-        let input = fs::read_to_string(&self.filepath)?;
-        Ok(process(&input, 9)?.to_string())
+    fn source(&self) -> &Source {
+        &self.source
     }
-}
 
-#[allow(unused_imports)]
-mod test {
-    use super::*;
+    fn parse(text: &str) -> Result<Vec<(Direction, u32)>, AocError> {
+        parse_instructions(text)
+    }
 
-    #[test]
-    fn it_returns_the_correct_first_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer1()?, "13");
-        Ok(())
+    fn part1(&self, instructions: &Self::Input, params: &Params) -> Result<Answer, AocError> {
+        Ok(Rope::new(instructions, tail_knots(params, "knots1")?)
+            .run()
+            .into())
     }
 
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test1() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test1.txt");
-        assert_eq!(solution.answer2()?, "1");
-        Ok(())
+    fn part2(&self, instructions: &Self::Input, params: &Params) -> Result<Answer, AocError> {
+        Ok(Rope::new(instructions, tail_knots(params, "knots2")?)
+            .run()
+            .into())
     }
 
-    #[test]
-    fn it_returns_the_correct_second_answer_for_test2() -> Result<(), Box<dyn Error>> {
-        let solution = Solution::new("test2.txt");
-        assert_eq!(solution.answer2()?, "36");
-        Ok(())
+    fn simulation<'a>(
+        &self,
+        instructions: &'a Self::Input,
+        params: &'a Params,
+        part: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        let knots = tail_knots(params, &format!("knots{part}"))?;
+        Ok(Some(Box::new(Rope::new(instructions, knots))))
     }
 }
@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum AocError {
+    /// The input file could not be read
+    Io {
+        day: usize,
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// The input does not match the expected format. Line and column are
+    /// 1-based; day and file are filled in by `with_context` once known.
+    Parse {
+        day: Option<usize>,
+        file: Option<PathBuf>,
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+    /// The input parsed, but no answer could be computed from it
+    Unsolvable { day: Option<usize>, reason: String },
+    /// A puzzle parameter is unknown or out of range
+    Param {
+        day: Option<usize>,
+        name: String,
+        reason: String,
+    },
+}
+
+impl AocError {
+    pub fn parse(line: usize, column: usize, reason: impl Into<String>) -> Self {
+        AocError::Parse {
+            day: None,
+            file: None,
+            line,
+            column,
+            reason: reason.into(),
+        }
+    }
+
+    /// A parse error pointing at `token`, which must be a slice of `line`
+    pub fn parse_at(
+        line_number: usize,
+        line: &str,
+        token: &str,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::parse(line_number, column_of(line, token), reason)
+    }
+
+    /// A parse error at byte `offset` of `text`, such as one reported by a
+    /// parser that only knows the span it failed on
+    pub fn parse_at_offset(text: &str, offset: usize, reason: impl Into<String>) -> Self {
+        let before = text.get(..offset).unwrap_or(text);
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Self::parse(line, before[line_start..].chars().count() + 1, reason)
+    }
+
+    pub fn unsolvable(reason: impl Into<String>) -> Self {
+        AocError::Unsolvable {
+            day: None,
+            reason: reason.into(),
+        }
+    }
+
+    pub fn param(name: impl Into<String>, reason: impl Into<String>) -> Self {
+        AocError::Param {
+            day: None,
+            name: name.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Attach the day to an error raised without knowing it
+    pub fn with_day(self, day: usize) -> Self {
+        match self {
+            AocError::Parse {
+                day: None,
+                file,
+                line,
+                column,
+                reason,
+            } => AocError::Parse {
+                day: Some(day),
+                file,
+                line,
+                column,
+                reason,
+            },
+            AocError::Unsolvable { day: None, reason } => AocError::Unsolvable {
+                day: Some(day),
+                reason,
+            },
+            AocError::Param {
+                day: None,
+                name,
+                reason,
+            } => AocError::Param {
+                day: Some(day),
+                name,
+                reason,
+            },
+            e => e,
+        }
+    }
+
+    /// Attach the day and input file to an error raised while parsing
+    pub fn with_context(self, day: usize, path: &Path) -> Self {
+        match self.with_day(day) {
+            AocError::Parse {
+                day,
+                file: None,
+                line,
+                column,
+                reason,
+            } => AocError::Parse {
+                day,
+                file: Some(path.to_path_buf()),
+                line,
+                column,
+                reason,
+            },
+            e => e,
+        }
+    }
+}
+
+/// 1-based column at which `token` starts within `line`, falling back to 1 if
+/// `token` is not a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    if offset <= line.len() {
+        line[..offset].chars().count() + 1
+    } else {
+        1
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            AocError::Io { day, path, source } => {
+                write!(f, "Day {day}: could not read {}: {source}", path.display())
+            }
+            AocError::Parse {
+                day,
+                file,
+                line,
+                column,
+                reason,
+            } => {
+                if let Some(day) = day {
+                    write!(f, "Day {day}: ")?;
+                }
+                match file {
+                    Some(file) => write!(f, "{}:{line}:{column}: {reason}", file.display()),
+                    None => write!(f, "line {line}, column {column}: {reason}"),
+                }
+            }
+            AocError::Unsolvable { day, reason } => {
+                if let Some(day) = day {
+                    write!(f, "Day {day}: ")?;
+                }
+                write!(f, "no answer: {reason}")
+            }
+            AocError::Param { day, name, reason } => {
+                if let Some(day) = day {
+                    write!(f, "Day {day}: ")?;
+                }
+                write!(f, "parameter {name}: {reason}")
+            }
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_column_of_a_token() {
+        let line = "move 1 from 2 to 1";
+        let token = line.split(' ').nth(2).unwrap();
+        assert_eq!(column_of(line, token), 8);
+        assert_eq!(column_of(line, "from"), 1);
+    }
+
+    #[test]
+    fn it_finds_the_line_and_column_of_an_offset() {
+        match AocError::parse_at_offset("ab\ncde\nf", 5, "") {
+            AocError::Parse { line, column, .. } => assert_eq!((line, column), (2, 3)),
+            _ => panic!("Expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn it_formats_parse_errors_with_their_context() {
+        let error = AocError::parse(3, 7, "Unrecognized character 'Q'")
+            .with_context(2, Path::new("data/2/input.txt"));
+        assert_eq!(
+            error.to_string(),
+            "Day 2: data/2/input.txt:3:7: Unrecognized character 'Q'"
+        );
+    }
+}
@@ -0,0 +1,605 @@
+use crate::params::Override;
+use fastrand::Rng;
+use std::fmt::Write;
+
+/// Settings for a generated input. The same knobs always generate the
+/// same input.
+#[derive(Clone, Copy, Debug)]
+pub struct Knobs {
+    pub seed: u64,
+    /// How large an input to generate; see [`generate`]
+    pub size: usize,
+}
+
+/// A random puzzle input, and the parameters it must be solved with
+pub struct Generated {
+    pub text: String,
+    pub params: Vec<Override>,
+}
+
+impl Generated {
+    fn text(text: String) -> Self {
+        Generated {
+            text,
+            params: vec![],
+        }
+    }
+}
+
+fn param(name: &str, value: impl Into<i64>) -> Override {
+    (name.to_string(), value.into())
+}
+
+/// A valid random input for `day`, or `None` if the day has no generator.
+///
+/// `size` is about the number of entries in the input, except that it is
+/// the number of stacks for day 5, of directories for day 7, the depth of
+/// the cave for day 14, and the size of the search area for day 15. Day 7
+/// nests directories about half as deep as there are of them. Some day 14
+/// paths start at x = 0, which sand can only reach in a cave over 500 deep.
+pub fn generate(day: usize, knobs: Knobs) -> Option<Generated> {
+    let mut rng = Rng::with_seed(knobs.seed);
+    let rng = &mut rng;
+    let size = knobs.size.max(1);
+    let generated = match day {
+        1 => Generated::text(calories(rng, size)),
+        2 => Generated::text(strategy_guide(rng, size)),
+        3 => Generated::text(rucksacks(rng, size)),
+        4 => Generated::text(section_pairs(rng, size)),
+        5 => Generated::text(crate_stacks(rng, size)),
+        6 => Generated::text(datastream(rng, size)),
+        7 => terminal_output(rng, size),
+        8 => Generated::text(tree_heights(rng, size)),
+        9 => Generated::text(rope_motions(rng, size)),
+        10 => Generated::text(cpu_program(rng, size)),
+        11 => monkey_notes(rng, size),
+        12 => Generated::text(heightmap(rng, size)),
+        13 => Generated::text(packet_pairs(rng, size)),
+        14 => Generated::text(rock_paths(rng, size)),
+        15 => sensor_report(rng, size),
+        _ => return None,
+    };
+    Some(generated)
+}
+
+fn calories(rng: &mut Rng, size: usize) -> String {
+    // Part 2 needs at least three elves
+    (0..size.max(3))
+        .map(|_| {
+            (0..rng.usize(1..=6))
+                .map(|_| format!("{}\n", rng.u32(1..=60_000)))
+                .collect::<String>()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn strategy_guide(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            let opponent = (b'A' + rng.u8(..3)) as char;
+            let second = (b'X' + rng.u8(..3)) as char;
+            format!("{opponent} {second}\n")
+        })
+        .collect()
+}
+
+/// One compartment of `len` items drawn from `own`, plus `shared`
+fn compartment(rng: &mut Rng, own: &[char], shared: char, len: usize) -> Vec<char> {
+    let mut items = (1..len)
+        .map(|_| own[rng.usize(..own.len())])
+        .collect::<Vec<char>>();
+    items.insert(rng.usize(..=items.len()), shared);
+    items
+}
+
+/// A rucksack holding `badge` and items from `pool`, whose compartments
+/// have exactly one item in common
+fn rucksack(rng: &mut Rng, pool: &[char], badge: char) -> String {
+    let mut pool = pool.to_vec();
+    pool.push(badge);
+    rng.shuffle(&mut pool);
+    let shared = pool.pop().unwrap_or(badge);
+    let (first_own, second_own) = pool.split_at(pool.len() / 2);
+    let len = rng.usize(2..=12);
+    let mut first = compartment(rng, first_own, shared, len);
+    let mut second = compartment(rng, second_own, shared, len);
+    // Make sure the badge is packed, if it is not the shared item
+    for (own, items) in [(first_own, &mut first), (second_own, &mut second)] {
+        if own.contains(&badge) {
+            let at = items.iter().position(|&c| c != shared).unwrap_or(0);
+            items[at] = badge;
+        }
+    }
+    first.into_iter().chain(second).chain(['\n']).collect()
+}
+
+fn rucksacks(rng: &mut Rng, size: usize) -> String {
+    let mut text = String::new();
+    for _ in 0..size.div_ceil(3) {
+        let mut items = ('a'..='z').chain('A'..='Z').collect::<Vec<char>>();
+        rng.shuffle(&mut items);
+        let badge = items.pop().unwrap_or('a');
+        // Every other item belongs to one elf, so only the badge is common
+        // to the whole group
+        for pool in items.chunks(items.len().div_ceil(3)) {
+            text += &rucksack(rng, pool, badge);
+        }
+    }
+    text
+}
+
+fn section_pairs(rng: &mut Rng, size: usize) -> String {
+    let mut range = || {
+        let start = rng.u32(1..=99);
+        format!("{start}-{}", rng.u32(start..=99))
+    };
+    (0..size)
+        .map(|_| format!("{},{}\n", range(), range()))
+        .collect()
+}
+
+fn crate_stacks(rng: &mut Rng, size: usize) -> String {
+    let mut stacks = (0..size)
+        .map(|_| (0..rng.usize(1..=6)).map(|_| rng.uppercase()).collect())
+        .collect::<Vec<Vec<char>>>();
+    let mut text = String::new();
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    for level in (0..height).rev() {
+        let row = stacks
+            .iter()
+            .map(|s| s.get(level).map_or("   ".to_string(), |c| format!("[{c}]")))
+            .collect::<Vec<String>>()
+            .join(" ");
+        writeln!(text, "{}", row.trim_end()).unwrap();
+    }
+    let labels = (1..=size)
+        .map(|n| format!("{n:^3}"))
+        .collect::<Vec<String>>()
+        .join(" ");
+    writeln!(text, "{}\n", labels.trim_end()).unwrap();
+
+    // Never take the last crate from a stack, so every stack has a top crate
+    // at the end
+    for _ in 0..size * 3 {
+        let src = rng.usize(..size);
+        let dst = rng.usize(..size);
+        if src == dst || stacks[src].len() < 2 {
+            continue;
+        }
+        let count = rng.usize(1..stacks[src].len());
+        let at = stacks[src].len() - count;
+        let moved = stacks[src].split_off(at);
+        stacks[dst].extend(moved);
+        writeln!(text, "move {count} from {} to {}", src + 1, dst + 1).unwrap();
+    }
+    text
+}
+
+fn datastream(rng: &mut Rng, size: usize) -> String {
+    // Letters from a small alphabet rarely form a long run of distinct ones
+    let alphabet = rng.u8(4..=16);
+    let mut chars = (0..size * 4)
+        .map(|_| (b'a' + rng.u8(..alphabet)) as char)
+        .collect::<Vec<char>>();
+    let mut marker = ('a'..='z').collect::<Vec<char>>();
+    rng.shuffle(&mut marker);
+    let at = rng.usize(..=chars.len());
+    chars.splice(at..at, marker.into_iter().take(14));
+    chars.into_iter().chain(['\n']).collect()
+}
+
+struct Directory {
+    name: String,
+    children: Vec<usize>,
+    files: Vec<(String, u32)>,
+}
+
+fn terminal_output(rng: &mut Rng, size: usize) -> Generated {
+    let mut dirs = vec![Directory {
+        name: "/".to_string(),
+        children: vec![],
+        files: vec![],
+    }];
+    for i in 1..size {
+        // Half of the directories extend the most recent one, making a deep
+        // tree; the rest go anywhere
+        let parent = if rng.bool() { i - 1 } else { rng.usize(..i) };
+        dirs[parent].children.push(i);
+        dirs.push(Directory {
+            name: format!("{}{i}", rng.lowercase()),
+            children: vec![],
+            files: vec![],
+        });
+    }
+    let mut total = 0;
+    for (i, dir) in dirs.iter_mut().enumerate() {
+        // The root always has a file, so there is something to delete
+        for j in 0..rng.usize(usize::from(i == 0)..=3) {
+            let size = rng.u32(1..=10_000);
+            total += i64::from(size);
+            dir.files
+                .push((format!("{}{i}.{j}", rng.lowercase()), size));
+        }
+    }
+
+    let mut text = String::new();
+    let mut list = |text: &mut String, dir: &Directory| {
+        let mut listing = dir
+            .children
+            .iter()
+            .map(|&c| format!("dir {}", dirs[c].name))
+            .chain(
+                dir.files
+                    .iter()
+                    .map(|(name, size)| format!("{size} {name}")),
+            )
+            .collect::<Vec<String>>();
+        rng.shuffle(&mut listing);
+        text.push_str("$ ls\n");
+        for entry in listing {
+            writeln!(text, "{entry}").unwrap();
+        }
+    };
+    text.push_str("$ cd /\n");
+    list(&mut text, &dirs[0]);
+    // Visit every directory depth first, without recursing
+    let mut path = vec![(0, 0)];
+    while let Some((dir, next)) = path.last_mut() {
+        match dirs[*dir].children.get(*next) {
+            Some(&child) => {
+                *next += 1;
+                writeln!(text, "$ cd {}", dirs[child].name).unwrap();
+                list(&mut text, &dirs[child]);
+                path.push((child, 0));
+            }
+            None => {
+                path.pop();
+                if !path.is_empty() {
+                    text.push_str("$ cd ..\n");
+                }
+            }
+        }
+    }
+
+    let disk_size = total + rng.i64(0..=total);
+    Generated {
+        text,
+        params: vec![
+            param("small_dir", rng.u32(1..=100_000)),
+            param("disk_size", disk_size),
+            param("required_space", disk_size - total + rng.i64(1..=total)),
+        ],
+    }
+}
+
+fn tree_heights(rng: &mut Rng, size: usize) -> String {
+    let columns = rng.usize(size / 2 + 1..=size);
+    (0..size)
+        .map(|_| {
+            (0..columns)
+                .map(|_| rng.digit(10))
+                .chain(['\n'])
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn rope_motions(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| {
+            format!(
+                "{} {}\n",
+                ["U", "D", "L", "R"][rng.usize(..4)],
+                rng.u32(1..=10)
+            )
+        })
+        .collect()
+}
+
+fn cpu_program(rng: &mut Rng, size: usize) -> String {
+    // Enough cycles to draw the whole screen, and sometimes a few more
+    let cycles = 240 + rng.usize(..=size);
+    let mut text = String::new();
+    let mut cycle = 0;
+    while cycle < cycles {
+        if rng.u8(..3) == 0 {
+            text.push_str("noop\n");
+            cycle += 1;
+        } else {
+            writeln!(text, "addx {}", rng.i32(-10..=10)).unwrap();
+            cycle += 2;
+        }
+    }
+    text
+}
+
+enum Operation {
+    Add(u128),
+    Multiply(u128),
+    Square,
+}
+
+struct Monkey {
+    items: Vec<u128>,
+    operation: Operation,
+    divisor: u128,
+    targets: [usize; 2],
+}
+
+/// Whether part 1 can be played out without any worry level overflowing
+fn part1_fits(monkeys: &[Monkey], rounds: usize) -> bool {
+    let mut items = monkeys.iter().map(|m| m.items.clone()).collect::<Vec<_>>();
+    for _ in 0..rounds {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for item in std::mem::take(&mut items[i]) {
+                let worry = match monkey.operation {
+                    Operation::Add(n) => item.checked_add(n),
+                    Operation::Multiply(n) => item.checked_mul(n),
+                    Operation::Square => item.checked_mul(item),
+                };
+                let Some(worry) = worry.map(|w| w / 3) else {
+                    return false;
+                };
+                let target = monkey.targets[usize::from(worry % monkey.divisor != 0)];
+                items[target].push(worry);
+            }
+        }
+    }
+    true
+}
+
+fn random_monkeys(rng: &mut Rng, count: usize) -> Vec<Monkey> {
+    let mut divisors = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+    rng.shuffle(&mut divisors);
+    (0..count)
+        .map(|i| {
+            let mut other = || loop {
+                let target = rng.usize(..count);
+                if target != i {
+                    break target;
+                }
+            };
+            let targets = [other(), other()];
+            Monkey {
+                items: (0..rng.usize(1..=4)).map(|_| rng.u128(50..100)).collect(),
+                operation: match rng.u8(..6) {
+                    0 => Operation::Square,
+                    1 | 2 => Operation::Multiply(rng.u128(2..=19)),
+                    _ => Operation::Add(rng.u128(1..=8)),
+                },
+                divisor: divisors[i],
+                targets,
+            }
+        })
+        .collect()
+}
+
+fn monkey_notes(rng: &mut Rng, size: usize) -> Generated {
+    let count = size.clamp(2, 8);
+    // Worry levels in part 1 are never reduced, so keep only notes whose
+    // levels fit in the solution's integers
+    let monkeys = loop {
+        let monkeys = random_monkeys(rng, count);
+        if part1_fits(&monkeys, 20) {
+            break monkeys;
+        }
+    };
+    let mut notes = vec![];
+    for (i, m) in monkeys.iter().enumerate() {
+        let items = m
+            .items
+            .iter()
+            .map(u128::to_string)
+            .collect::<Vec<String>>()
+            .join(", ");
+        let operation = match m.operation {
+            Operation::Add(n) => format!("+ {n}"),
+            Operation::Multiply(n) => format!("* {n}"),
+            Operation::Square => "* old".to_string(),
+        };
+        notes.push(format!(
+            "Monkey {i}:\n  Starting items: {items}\n  Operation: new = old {operation}\n  \
+             Test: divisible by {}\n    If true: throw to monkey {}\n    \
+             If false: throw to monkey {}\n",
+            m.divisor, m.targets[0], m.targets[1]
+        ));
+    }
+    Generated {
+        text: notes.join("\n"),
+        params: vec![param("rounds2", 1000)],
+    }
+}
+
+fn heightmap(rng: &mut Rng, size: usize) -> String {
+    // A path along one row climbs from a to z, so the goal can always be
+    // reached; the cells around it are random
+    let columns = size.max(26);
+    let rows = rng.usize(1..=size.max(2));
+    let path_row = rng.usize(..rows);
+    let mut text = String::new();
+    for r in 0..rows {
+        for c in 0..columns {
+            text.push(match (r == path_row, c) {
+                (true, 0) => 'S',
+                (true, c) if c == columns - 1 => 'E',
+                (true, c) => (b'a' + (c * 26 / columns) as u8) as char,
+                _ => (b'a' + rng.u8(..26)) as char,
+            });
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> String {
+    let items = (0..rng.usize(..=4))
+        .map(|_| {
+            if depth < 4 && rng.bool() {
+                packet(rng, depth + 1)
+            } else {
+                rng.u8(..=10).to_string()
+            }
+        })
+        .collect::<Vec<String>>();
+    format!("[{}]", items.join(","))
+}
+
+fn packet_pairs(rng: &mut Rng, size: usize) -> String {
+    // A packet like [2] or [[[6]]] is neither before nor after a divider
+    // packet, so leave those out
+    let is_divider =
+        |p: &str| !p.contains(',') && matches!(p.trim_matches(|c| c == '[' || c == ']'), "2" | "6");
+    let mut next_packet = || loop {
+        let p = packet(rng, 0);
+        if !is_divider(&p) {
+            break p;
+        }
+    };
+    (0..size)
+        .map(|_| format!("{}\n{}\n", next_packet(), next_packet()))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn rock_paths(rng: &mut Rng, size: usize) -> String {
+    let depth = size.max(2) as i64;
+    let mut text = String::new();
+    for i in 0..(size / 4).max(1) {
+        // Some paths hug x = 0, whether or not the sand can reach that far
+        let mut x = match rng.usize(..4) {
+            0 => rng.i64(0..=5),
+            _ => rng.i64(500 - depth..=500 + depth).max(0),
+        };
+        let mut y = rng.i64(1..=depth);
+        // The first path spans the deepest row, so the cave is `size` deep
+        if i == 0 {
+            y = depth;
+        }
+        let mut points = vec![format!("{x},{y}")];
+        for segment in 0..rng.usize(1..=4) {
+            let length = rng.i64(-5..=5);
+            if segment % 2 == 0 {
+                x = (x + length).max(0);
+            } else {
+                y = (y + length).clamp(1, depth);
+            }
+            points.push(format!("{x},{y}"));
+        }
+        writeln!(text, "{}", points.join(" -> ")).unwrap();
+    }
+    text
+}
+
+type Position = (i64, i64);
+
+fn distance((x1, y1): Position, (x2, y2): Position) -> i64 {
+    (x1 - x2).abs() + (y1 - y2).abs()
+}
+
+/// Sensors, each with its beacon, that leave only `beacon` uncovered in
+/// the square from 0 to `limit`. Four sensors at least `limit` out along
+/// the diagonals from the distress beacon, each reaching to just short of
+/// it, cover a quadrant of the square each; the rest are scattered about.
+fn place_sensors(rng: &mut Rng, limit: i64, beacon: Position) -> Vec<(Position, Position)> {
+    // Each sensor's beacon must be closer to it than any other beacon
+    let consistent = |sensors: &[(Position, Position)], sensor, closest| {
+        let radius = distance(sensor, closest);
+        sensors.iter().all(|&(s, b)| {
+            s != sensor && distance(sensor, b) > radius && distance(s, closest) > distance(s, b)
+        })
+    };
+    let mut sensors = vec![];
+    let d = rng.i64(limit..=limit * 2);
+    for (sx, sy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+        let sensor = (beacon.0 + sx * d, beacon.1 + sy * d);
+        // On the far side of the sensor's reach, away from the other sensors
+        let dx = rng.i64(0..2 * d);
+        let closest = (sensor.0 + sx * dx, sensor.1 + sy * (2 * d - 1 - dx));
+        sensors.push((sensor, closest));
+    }
+    for _ in 0..rng.usize(0..=100) {
+        let sensor = (
+            rng.i64(-limit / 2..=limit * 3 / 2),
+            rng.i64(-limit / 2..=limit * 3 / 2),
+        );
+        // Reach as far as possible without covering the distress beacon
+        let radius = distance(sensor, beacon) - 1;
+        if radius < 1 {
+            continue;
+        }
+        let dx = rng.i64(-radius..=radius);
+        let dy = (radius - dx.abs()) * if rng.bool() { 1 } else { -1 };
+        let closest = (sensor.0 + dx, sensor.1 + dy);
+        if consistent(&sensors, sensor, closest) {
+            sensors.push((sensor, closest));
+        }
+    }
+    rng.shuffle(&mut sensors);
+    sensors
+}
+
+fn sensor_report(rng: &mut Rng, size: usize) -> Generated {
+    let limit = size.max(4) as i64;
+    let beacon = (rng.i64(0..=limit), rng.i64(0..=limit));
+    let sensors = place_sensors(rng, limit, beacon);
+    let text = sensors
+        .iter()
+        .map(|((sx, sy), (bx, by))| {
+            format!("Sensor at x={sx}, y={sy}: closest beacon is at x={bx}, y={by}\n")
+        })
+        .collect();
+    Generated {
+        text,
+        params: vec![param("row", rng.i64(0..=limit)), param("limit", limit)],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_generates_the_same_input_for_the_same_knobs() {
+        for day in 1..=15 {
+            let knobs = Knobs { seed: 7, size: 10 };
+            let first = generate(day, knobs).map(|g| g.text);
+            assert!(first.is_some(), "Day {day}");
+            assert_eq!(first, generate(day, knobs).map(|g| g.text), "Day {day}");
+        }
+        assert!(generate(16, Knobs { seed: 7, size: 10 }).is_none());
+    }
+
+    #[test]
+    fn it_places_sensors_for_the_full_search_area() {
+        let generated = generate(
+            15,
+            Knobs {
+                seed: 2,
+                size: 4_000_000,
+            },
+        )
+        .unwrap();
+        assert!(generated.text.lines().count() >= 4);
+        assert!(generated.params.contains(&param("limit", 4_000_000)));
+    }
+
+    #[test]
+    fn it_gives_the_smallest_filesystem_something_to_delete() {
+        for seed in 0..20 {
+            let generated = generate(7, Knobs { seed, size: 1 }).unwrap();
+            assert!(generated
+                .text
+                .lines()
+                .any(|l| l.starts_with(char::is_numeric)));
+        }
+    }
+
+    #[test]
+    fn it_draws_more_than_nine_stacks() {
+        let generated = generate(5, Knobs { seed: 1, size: 12 }).unwrap();
+        let (drawing, _) = generated.text.split_once("\n\n").unwrap();
+        let labels = drawing.lines().last().unwrap();
+        assert_eq!(labels.split_whitespace().last(), Some("12"));
+    }
+}
@@ -0,0 +1,470 @@
+use crate::error::AocError;
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use std::num::ParseIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// An integer type points can be made of
+pub trait Coord:
+    Copy
+    + Debug
+    + Default
+    + Ord
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + FromStr<Err = ParseIntError>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// The distance between `self` and `other`, which cannot overflow
+    /// even for unsigned types
+    fn distance(self, other: Self) -> Self {
+        if self > other {
+            self - other
+        } else {
+            other - self
+        }
+    }
+}
+
+macro_rules! coord {
+    ($($t:ty),*) => {
+        $(impl Coord for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+coord!(i32, i64, isize, u32, u64, usize);
+
+/// The sign of `n` as -1, 0 or 1
+fn signum<T: Coord + Neg<Output = T>>(n: T) -> T {
+    match n.cmp(&T::ZERO) {
+        std::cmp::Ordering::Less => -T::ONE,
+        std::cmp::Ordering::Equal => T::ZERO,
+        std::cmp::Ordering::Greater => T::ONE,
+    }
+}
+
+/// Parse the coordinates of a point from `token`, a slice of the input
+/// `line`, as comma separated numbers that may each be named, as in
+/// `498,4` or `x=2, y=18`
+fn parse_coords<T: Coord, const N: usize>(
+    line_number: usize,
+    line: &str,
+    token: &str,
+    names: [&str; N],
+) -> Result<[T; N], AocError> {
+    let parts = token.split(',').collect::<Vec<&str>>();
+    if parts.len() != N {
+        let expected = names.map(|name| format!("{name}=N")).join(", ");
+        return Err(AocError::parse_at(
+            line_number,
+            line,
+            token,
+            format!("Expected a point as {expected}"),
+        ));
+    }
+    let mut coords = [T::ZERO; N];
+    for ((coord, part), name) in coords.iter_mut().zip(parts).zip(names) {
+        let part = part.trim();
+        let value = match part.split_once('=') {
+            Some((label, value)) if label.trim() == name => value.trim(),
+            Some((label, _)) => {
+                return Err(AocError::parse_at(
+                    line_number,
+                    line,
+                    part,
+                    format!("Expected {name}=N, found {label}="),
+                ))
+            }
+            None => part,
+        };
+        *coord = value.parse().map_err(|e| {
+            AocError::parse_at(
+                line_number,
+                line,
+                value,
+                format!("Malformed {name} coord: {e}"),
+            )
+        })?;
+    }
+    Ok(coords)
+}
+
+/// A point on a plane. Like the rows of a puzzle's map, y grows downwards.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Coord> Point2<T> {
+    pub const ORIGIN: Self = Point2 {
+        x: T::ZERO,
+        y: T::ZERO,
+    };
+
+    pub fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x.distance(other.x).max(self.y.distance(other.y))
+    }
+
+    /// The sign of each coordinate, so the step towards the origin is the
+    /// negated signum
+    pub fn signum(&self) -> Self
+    where
+        T: Neg<Output = T>,
+    {
+        Point2::new(signum(self.x), signum(self.y))
+    }
+
+    /// The orthogonal neighbours, in the order of [`Direction::ALL`]
+    pub fn neighbours4(&self) -> [Self; 4]
+    where
+        T: Neg<Output = T>,
+    {
+        Direction::ALL.map(|d| *self + d.delta())
+    }
+
+    /// The orthogonal and diagonal neighbours, row by row
+    pub fn neighbours8(&self) -> [Self; 8]
+    where
+        T: Neg<Output = T>,
+    {
+        let (o, i) = (T::ZERO, T::ONE);
+        [
+            (-i, -i),
+            (o, -i),
+            (i, -i),
+            (-i, o),
+            (i, o),
+            (-i, i),
+            (o, i),
+            (i, i),
+        ]
+        .map(|(dx, dy)| *self + Point2::new(dx, dy))
+    }
+
+    /// Parse `x,y` or `x=X, y=Y` from `token`, a slice of the input `line`
+    pub fn parse_at(line_number: usize, line: &str, token: &str) -> Result<Self, AocError> {
+        let [x, y] = parse_coords(line_number, line, token, ["x", "y"])?;
+        Ok(Point2 { x, y })
+    }
+}
+
+impl<T: Coord> FromStr for Point2<T> {
+    type Err = AocError;
+
+    /// Parse `x,y` or `x=X, y=Y`, reporting errors as if on line 1
+    fn from_str(s: &str) -> Result<Self, AocError> {
+        Self::parse_at(1, s, s)
+    }
+}
+
+impl<T: Display> Display for Point2<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A point in space
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Coord> Point3<T> {
+    pub const ORIGIN: Self = Point3 {
+        x: T::ZERO,
+        y: T::ZERO,
+        z: T::ZERO,
+    };
+
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+
+    pub fn manhattan(&self, other: &Self) -> T {
+        self.x.distance(other.x) + self.y.distance(other.y) + self.z.distance(other.z)
+    }
+
+    pub fn chebyshev(&self, other: &Self) -> T {
+        self.x
+            .distance(other.x)
+            .max(self.y.distance(other.y))
+            .max(self.z.distance(other.z))
+    }
+
+    /// The six neighbours sharing a face
+    pub fn neighbours6(&self) -> [Self; 6]
+    where
+        T: Neg<Output = T>,
+    {
+        let (o, i) = (T::ZERO, T::ONE);
+        [
+            (-i, o, o),
+            (i, o, o),
+            (o, -i, o),
+            (o, i, o),
+            (o, o, -i),
+            (o, o, i),
+        ]
+        .map(|(dx, dy, dz)| *self + Point3::new(dx, dy, dz))
+    }
+
+    /// Parse `x,y,z` or `x=X, y=Y, z=Z` from `token`, a slice of the input
+    /// `line`
+    pub fn parse_at(line_number: usize, line: &str, token: &str) -> Result<Self, AocError> {
+        let [x, y, z] = parse_coords(line_number, line, token, ["x", "y", "z"])?;
+        Ok(Point3 { x, y, z })
+    }
+}
+
+impl<T: Coord> FromStr for Point3<T> {
+    type Err = AocError;
+
+    /// Parse `x,y,z` or `x=X, y=Y, z=Z`, reporting errors as if on line 1
+    fn from_str(s: &str) -> Result<Self, AocError> {
+        Self::parse_at(1, s, s)
+    }
+}
+
+impl<T: Display> Display for Point3<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{},{},{}", self.x, self.y, self.z)
+    }
+}
+
+macro_rules! ops {
+    ($point:ident { $($field:ident),* }) => {
+        impl<T: Coord> Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                $point { $($field: self.$field + other.$field),* }
+            }
+        }
+
+        impl<T: Coord> Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                $point { $($field: self.$field - other.$field),* }
+            }
+        }
+
+        impl<T: Coord> Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, n: T) -> Self {
+                $point { $($field: self.$field * n),* }
+            }
+        }
+
+        impl<T: Coord + Neg<Output = T>> Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                $point { $($field: -self.$field),* }
+            }
+        }
+
+        impl<T: Coord> AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Coord> SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                *self = *self - other;
+            }
+        }
+    };
+}
+
+ops!(Point2 { x, y });
+ops!(Point3 { x, y, z });
+
+/// One of the four orthogonal directions on a map, where up is towards
+/// smaller y
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Read `U`, `D`, `L` or `R`, or a compass point `N`, `S`, `W` or `E`
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'U' | 'N' => Some(Direction::Up),
+            'D' | 'S' => Some(Direction::Down),
+            'L' | 'W' => Some(Direction::Left),
+            'R' | 'E' => Some(Direction::Right),
+            _ => None,
+        }
+    }
+
+    /// The step of one in this direction
+    pub fn delta<T: Coord + Neg<Output = T>>(self) -> Point2<T> {
+        let (o, i) = (T::ZERO, T::ONE);
+        match self {
+            Direction::Up => Point2::new(o, -i),
+            Direction::Down => Point2::new(o, i),
+            Direction::Left => Point2::new(-i, o),
+            Direction::Right => Point2::new(i, o),
+        }
+    }
+
+    /// Turn a quarter clockwise
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// Turn a quarter anticlockwise
+    pub fn turn_left(self) -> Self {
+        self.turn_right().reverse()
+    }
+
+    pub fn reverse(self) -> Self {
+        self.turn_right().turn_right()
+    }
+}
+
+/// The smallest rectangle holding a set of points, edges included
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds<T> {
+    pub min: Point2<T>,
+    pub max: Point2<T>,
+}
+
+impl<T: Coord> Bounds<T> {
+    /// The bounds of `points`, or `None` if there are none
+    pub fn of(points: impl IntoIterator<Item = Point2<T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(
+            Bounds {
+                min: first,
+                max: first,
+            },
+            Bounds::including,
+        ))
+    }
+
+    /// These bounds grown to hold `p`
+    pub fn including(self, p: Point2<T>) -> Self {
+        Bounds {
+            min: Point2::new(self.min.x.min(p.x), self.min.y.min(p.y)),
+            max: Point2::new(self.max.x.max(p.x), self.max.y.max(p.y)),
+        }
+    }
+
+    pub fn contains(&self, p: &Point2<T>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    pub fn width(&self) -> T {
+        self.max.x - self.min.x + T::ONE
+    }
+
+    pub fn height(&self) -> T {
+        self.max.y - self.min.y + T::ONE
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_measures_distances() {
+        let (a, b) = (Point2::new(2, 18), Point2::new(-2, 15));
+        assert_eq!((a.manhattan(&b), a.chebyshev(&b)), (7, 4));
+        let (a, b) = (Point2::<usize>::new(0, 5), Point2::new(3, 1));
+        assert_eq!((a.manhattan(&b), a.chebyshev(&b)), (7, 4));
+        let c = Point3::new(1, 2, 3);
+        assert_eq!(c.manhattan(&Point3::ORIGIN), 6);
+        assert_eq!(c.chebyshev(&Point3::ORIGIN), 3);
+    }
+
+    #[test]
+    fn it_parses_both_point_formats() -> Result<(), AocError> {
+        assert_eq!("498,4".parse::<Point2<i32>>()?, Point2::new(498, 4));
+        assert_eq!("x=-2, y=15".parse::<Point2<i64>>()?, Point2::new(-2, 15));
+        assert_eq!("1,-2,3".parse::<Point3<i32>>()?, Point3::new(1, -2, 3));
+
+        let line = "Sensor at x=2, z=18: closest beacon";
+        let error = Point2::<i32>::parse_at(4, line, &line[10..19]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 16: Expected y=N, found z="
+        );
+        let error = "5,x".parse::<Point2<i32>>().unwrap_err().to_string();
+        assert!(
+            error.starts_with("line 1, column 3: Malformed y coord"),
+            "{error}"
+        );
+        let error = "5".parse::<Point2<u32>>().unwrap_err().to_string();
+        assert_eq!(error, "line 1, column 1: Expected a point as x=N, y=N");
+        Ok(())
+    }
+
+    #[test]
+    fn it_turns_and_steps_in_directions() {
+        let up = Direction::Up;
+        assert_eq!(up.turn_right(), Direction::Right);
+        assert_eq!(up.turn_left(), Direction::Left);
+        assert_eq!(up.reverse(), Direction::Down);
+        assert_eq!(up.turn_left().turn_left().turn_left().turn_left(), up);
+        assert_eq!(Point2::new(3, 3) + up.delta() * 2, Point2::new(3, 1));
+        assert_eq!(Direction::from_letter('R'), Some(Direction::Right));
+        assert_eq!(Point2::new(-4, 0).signum(), Point2::new(-1, 0));
+        assert_eq!(Point2::new(0, 0).neighbours8().len(), 8);
+        assert!(!Point2::new(0, 0).neighbours4().contains(&Point2::new(1, 1)));
+    }
+
+    #[test]
+    fn it_bounds_points() {
+        let points = [
+            Point2::new(498, 4),
+            Point2::new(503, 9),
+            Point2::new(494, 6),
+        ];
+        let bounds = Bounds::of(points).unwrap();
+        assert_eq!(bounds.min, Point2::new(494, 4));
+        assert_eq!(bounds.max, Point2::new(503, 9));
+        assert_eq!((bounds.width(), bounds.height()), (10, 6));
+        assert!(bounds.contains(&Point2::new(500, 9)));
+        assert!(!bounds.contains(&Point2::new(500, 10)));
+        assert_eq!(Bounds::<i32>::of([]), None);
+    }
+}
@@ -0,0 +1,318 @@
+use crate::error::AocError;
+use crate::geom::Point2;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::{Index, IndexMut};
+
+/// A position in a [`Grid`], with x the column and y the row, counted
+/// from the top left
+pub type Pos = Point2<usize>;
+
+/// A dense rectangle of cells, stored row by row
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    rows: usize,
+    columns: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(rows: usize, columns: usize, mut cell: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..rows)
+            .flat_map(|y| (0..columns).map(move |x| Point2::new(x, y)))
+            .map(&mut cell)
+            .collect();
+        Grid {
+            rows,
+            columns,
+            cells,
+        }
+    }
+
+    /// Parse a map with a character per cell and a line per row, turning
+    /// each character into a cell with `cell`. A reason `cell` gives for
+    /// rejecting a character is reported at that character's position.
+    pub fn parse(
+        text: &str,
+        mut cell: impl FnMut(Pos, char) -> Result<T, String>,
+    ) -> Result<Self, AocError> {
+        let mut cells = vec![];
+        let mut columns = None;
+        let mut rows = 0;
+        for (r, line) in text.lines().enumerate() {
+            let width = line.chars().count();
+            match columns {
+                None => columns = Some(width),
+                Some(columns) if columns != width => {
+                    return Err(AocError::parse(
+                        r + 1,
+                        1,
+                        format!("Expected a row of {columns}, found {width}"),
+                    ))
+                }
+                Some(_) => {}
+            }
+            for (c, ch) in line.chars().enumerate() {
+                cells.push(
+                    cell(Point2::new(c, r), ch)
+                        .map_err(|reason| AocError::parse(r + 1, c + 1, reason))?,
+                );
+            }
+            rows += 1;
+        }
+        match columns {
+            Some(columns) if columns > 0 => Ok(Grid {
+                rows,
+                columns,
+                cells,
+            }),
+            _ => Err(AocError::parse(1, 1, "Empty grid")),
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.y < self.rows && pos.x < self.columns
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.cells[pos.y * self.columns + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        match self.contains(pos) {
+            true => Some(&mut self.cells[pos.y * self.columns + pos.x]),
+            false => None,
+        }
+    }
+
+    /// The position one `step` from `pos`, if it is inside the grid
+    pub fn step(&self, pos: Pos, step: Point2<isize>) -> Option<Pos> {
+        let pos = Point2::new(
+            pos.x.checked_add_signed(step.x)?,
+            pos.y.checked_add_signed(step.y)?,
+        );
+        self.contains(pos).then_some(pos)
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |y| (0..columns).map(move |x| Point2::new(x, y)))
+    }
+
+    /// Every cell with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The first position, row by row, whose cell matches `predicate`
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(p, _)| p)
+    }
+
+    /// The neighbours of `pos` in the grid, one of `steps` away
+    pub fn neighbours<'a, I>(&'a self, pos: Pos, steps: I) -> impl Iterator<Item = Pos> + 'a
+    where
+        I: IntoIterator<Item = Point2<isize>>,
+        I::IntoIter: 'a,
+    {
+        steps
+            .into_iter()
+            .filter_map(move |step| self.step(pos, step))
+    }
+
+    /// The orthogonal neighbours, as in [`Point2::neighbours4`]
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, Point2::ORIGIN.neighbours4())
+    }
+
+    /// The orthogonal and diagonal neighbours, as in [`Point2::neighbours8`]
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours(pos, Point2::ORIGIN.neighbours8())
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.cells[r * self.columns..(r + 1) * self.columns]
+    }
+
+    /// The cells of column `c`, top to bottom. Panics if there is no such
+    /// column, as [`Grid::row`] does for rows.
+    pub fn column(&self, c: usize) -> impl DoubleEndedIterator<Item = &T> {
+        assert!(
+            c < self.columns,
+            "Column {c} is outside a grid {} wide",
+            self.columns
+        );
+        self.cells[c..].iter().step_by(self.columns)
+    }
+
+    /// The cells seen looking from `pos` repeatedly by `step`, nearest
+    /// first and not including `pos` itself, up to the edge of the grid
+    pub fn line_of_sight(&self, pos: Pos, step: Point2<isize>) -> impl Iterator<Item = &T> {
+        std::iter::successors(self.step(pos, step), move |&p| self.step(p, step)).map(|p| &self[p])
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            rows: self.rows,
+            columns: self.columns,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+
+    /// Flip the grid over its diagonal, so rows become columns
+    pub fn transpose(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid::from_fn(self.columns, self.rows, |p| {
+            self[Point2::new(p.y, p.x)].clone()
+        })
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(rows: usize, columns: usize, fill: T) -> Self {
+        Grid {
+            rows,
+            columns,
+            cells: vec![fill; rows * columns],
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside a {}x{} grid", self.columns, self.rows))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (rows, columns) = (self.rows, self.columns);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside a {columns}x{rows} grid"))
+    }
+}
+
+/// Each cell's own rendering, with a line per row
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        for r in 0..self.rows {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for cell in self.row(r) {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn at(x: usize, y: usize) -> Pos {
+        Point2::new(x, y)
+    }
+
+    fn digits(text: &str) -> Result<Grid<u32>, AocError> {
+        Grid::parse(text, |_, ch| {
+            ch.to_digit(10).ok_or(format!("{ch:?} is not a digit"))
+        })
+    }
+
+    #[test]
+    fn it_parses_and_displays_a_character_map() -> Result<(), AocError> {
+        let grid = digits("123\n456\n")?;
+        assert_eq!((grid.rows(), grid.columns()), (2, 3));
+        assert_eq!(grid[at(0, 1)], 4);
+        assert_eq!(grid.get(at(0, 2)), None);
+        assert_eq!(grid.get(at(3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+        assert_eq!(grid.position(|&d| d > 4), Some(at(1, 1)));
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_where_a_map_is_malformed() {
+        let error = digits("123\n4x6\n").unwrap_err().to_string();
+        assert_eq!(error, "line 2, column 2: 'x' is not a digit");
+        let error = digits("123\n45\n").unwrap_err().to_string();
+        assert_eq!(error, "line 2, column 1: Expected a row of 3, found 2");
+        assert!(digits("").is_err());
+    }
+
+    #[test]
+    fn it_finds_neighbours_within_bounds() -> Result<(), AocError> {
+        let grid = digits("123\n456\n789")?;
+        let around = |n: Vec<Pos>| n.iter().map(|&p| grid[p]).collect::<Vec<u32>>();
+        assert_eq!(around(grid.neighbours4(at(0, 0)).collect()), [4, 2]);
+        assert_eq!(
+            around(grid.neighbours8(at(1, 1)).collect()),
+            [1, 2, 3, 4, 6, 7, 8, 9]
+        );
+        assert_eq!(grid.neighbours8(at(2, 2)).count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn it_iterates_rows_columns_and_lines_of_sight() -> Result<(), AocError> {
+        let grid = digits("123\n456\n789")?;
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.column(2).copied().collect::<Vec<u32>>(), [3, 6, 9]);
+        assert_eq!(
+            grid.column(0).rev().copied().collect::<Vec<u32>>(),
+            [7, 4, 1]
+        );
+        let sight = |step| {
+            grid.line_of_sight(at(1, 1), step)
+                .copied()
+                .collect::<Vec<u32>>()
+        };
+        assert_eq!(sight(Point2::new(1, 0)), [6]);
+        assert_eq!(sight(Point2::new(-1, -1)), [1]);
+        assert_eq!(grid.line_of_sight(at(0, 0), Point2::new(0, 1)).count(), 2);
+        assert_eq!(grid.line_of_sight(at(0, 0), Point2::new(0, -1)).count(), 0);
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Column 3 is outside a grid 3 wide")]
+    fn it_has_no_column_past_the_last() {
+        let grid = Grid::new(3, 3, 0);
+        grid.column(3).count();
+    }
+
+    #[test]
+    #[should_panic(expected = "Column 0 is outside a grid 0 wide")]
+    fn it_has_no_columns_when_empty() {
+        let grid = Grid::new(2, 0, 0);
+        grid.column(0).count();
+    }
+
+    #[test]
+    fn it_transposes_and_maps() -> Result<(), AocError> {
+        let grid = digits("12\n34\n56")?;
+        assert_eq!(grid.transpose().to_string(), "135\n246");
+        assert_eq!(grid.transpose().transpose(), grid);
+        assert_eq!(grid.map(|d| d % 2).to_string(), "10\n10\n10");
+        assert_eq!(Grid::new(2, 2, '.').to_string(), "..\n..");
+        Ok(())
+    }
+}
@@ -1,17 +1,62 @@
+#[cfg(feature = "day1")]
 pub mod day1;
+#[cfg(feature = "day10")]
 pub mod day10;
+#[cfg(feature = "day11")]
 pub mod day11;
+#[cfg(feature = "day12")]
 pub mod day12;
+#[cfg(feature = "day13")]
 pub mod day13;
+#[cfg(feature = "day14")]
 pub mod day14;
+#[cfg(feature = "day15")]
 pub mod day15;
+#[cfg(feature = "day2")]
 pub mod day2;
+#[cfg(feature = "day3")]
 pub mod day3;
+#[cfg(feature = "day4")]
 pub mod day4;
+#[cfg(feature = "day5")]
 pub mod day5;
+#[cfg(feature = "day6")]
 pub mod day6;
+#[cfg(feature = "day7")]
 pub mod day7;
+#[cfg(feature = "day8")]
 pub mod day8;
+#[cfg(feature = "day9")]
 pub mod day9;
 
+pub mod answer;
+#[cfg(feature = "cli")]
+pub mod bench;
+#[cfg(feature = "net")]
+pub mod client;
+pub mod error;
+#[cfg(feature = "cli")]
+pub mod generate;
+pub mod geom;
+pub mod grid;
+pub mod logging;
+pub mod manifest;
+pub mod parallel;
+pub mod params;
+pub mod parse;
+#[cfg(feature = "cli")]
+pub mod reference;
+pub mod registry;
+#[cfg(feature = "cli")]
+pub mod repl;
+pub mod report;
+#[cfg(feature = "cli")]
+pub mod scaffold;
+#[cfg(feature = "server")]
+pub mod server;
 pub mod solvable;
+#[cfg(feature = "net")]
+pub mod submit;
+pub mod verify;
+#[cfg(feature = "cli")]
+pub mod watch;
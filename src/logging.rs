@@ -0,0 +1,135 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use std::str::FromStr;
+
+/// Environment variable holding a filter such as `debug` or
+/// `warn,day13=trace,day15=debug`
+pub const LOG_VAR: &str = "AOC_LOG";
+
+/// Leading path of every target logged from this crate, which filters leave
+/// out so that `day13` selects the module `adventofcode2022::day13`
+const CRATE_PREFIX: &str = concat!(env!("CARGO_CRATE_NAME"), "::");
+
+/// Which levels to log: a default, and levels for particular targets
+#[derive(Debug, PartialEq)]
+pub struct Filter {
+    default: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    /// Only warnings and errors
+    pub fn quiet() -> Self {
+        Filter {
+            default: LevelFilter::Warn,
+            targets: vec![],
+        }
+    }
+
+    /// Raise the default level by one for each `-v`
+    pub fn with_verbosity(mut self, verbosity: u8) -> Self {
+        let level = match verbosity {
+            0 => return self,
+            1 => LevelFilter::Info,
+            2 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        };
+        self.default = self.default.max(level);
+        self
+    }
+
+    /// The most verbose level of `target`, such as `day13` or `day13::packet`
+    pub fn level(&self, target: &str) -> LevelFilter {
+        let target = target.strip_prefix(CRATE_PREFIX).unwrap_or(target);
+        self.targets
+            .iter()
+            .filter(|(t, _)| {
+                target == t
+                    || target
+                        .strip_prefix(t.as_str())
+                        .is_some_and(|r| r.starts_with("::"))
+            })
+            .max_by_key(|(t, _)| t.len())
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut filter = Filter::quiet();
+        let level = |s: &str| {
+            s.trim()
+                .parse::<LevelFilter>()
+                .map_err(|_| format!("Unknown log level \"{}\"", s.trim()))
+        };
+        for directive in spec.split(',').filter(|d| !d.trim().is_empty()) {
+            match directive.split_once('=') {
+                Some((target, l)) => filter.targets.push((target.trim().to_string(), level(l)?)),
+                None => filter.default = level(directive)?,
+            }
+        }
+        Ok(filter)
+    }
+}
+
+/// Writes diagnostics to stderr, so they never mix with answers on stdout
+struct StderrLogger {
+    filter: Filter,
+}
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let target = record.target();
+            let target = target.strip_prefix(CRATE_PREFIX).unwrap_or(target);
+            eprintln!("{:<5} {target}: {}", record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the logger for the rest of the process
+pub fn init(filter: Filter) -> Result<(), log::SetLoggerError> {
+    log::set_max_level(filter.max_level());
+    log::set_boxed_logger(Box::new(StderrLogger { filter }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_a_default_and_per_day_levels() -> Result<(), String> {
+        let filter = Filter::from_str("info,day13=trace, day15 = debug")?;
+        assert_eq!(filter.level("adventofcode2022::day1"), LevelFilter::Info);
+        assert_eq!(filter.level("adventofcode2022::day13"), LevelFilter::Trace);
+        assert_eq!(filter.level("day15"), LevelFilter::Debug);
+        assert_eq!(filter.level("day150"), LevelFilter::Info);
+        assert!(Filter::from_str("day13=loud").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_raises_the_default_level_for_verbosity() -> Result<(), String> {
+        assert_eq!(
+            Filter::quiet().with_verbosity(2).level("day1"),
+            LevelFilter::Debug
+        );
+        let filter = Filter::from_str("trace")?.with_verbosity(1);
+        assert_eq!(filter.level("day1"), LevelFilter::Trace);
+        Ok(())
+    }
+}
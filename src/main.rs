@@ -1,11 +1,900 @@
-use adventofcode2022::{day15, solvable::Solvable};
+use adventofcode2022::answer::Answer;
+use adventofcode2022::bench::{bench_day, format_duration, Baseline};
+use adventofcode2022::client::{self, Client, Config, Fetched};
+use adventofcode2022::generate::{generate, Knobs};
+use adventofcode2022::logging::{self, Filter, LOG_VAR};
+use adventofcode2022::manifest::Manifest;
+use adventofcode2022::parallel;
+use adventofcode2022::params::{parse_override, Override};
+use adventofcode2022::reference::compare;
+use adventofcode2022::registry::{Registry, Solver};
+use adventofcode2022::repl;
+use adventofcode2022::report::{solve_part, Outcome, PartResult, Record};
+use adventofcode2022::scaffold;
+use adventofcode2022::solvable::{self, Source};
+use adventofcode2022::submit::{self, SubmissionLog, Verdict};
+use adventofcode2022::verify::{self, verify_day, Check, Status};
+use adventofcode2022::watch::{quick_inputs, Watcher};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use std::env;
 use std::error::Error;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, ExitCode};
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut solution = day15::Solution::new("input.txt");
-    solution.set_row_to_inspect(2_000_000);
-    solution.set_limit(4_000_000);
-    println!("{}", solution.answer1()?);
-    println!("{}", solution.answer2()?);
+#[derive(Parser)]
+#[command(name = "aoc", about = "Advent of Code 2022 solutions")]
+struct Cli {
+    /// Directory holding the data for each day [default: $AOC_DATA_DIR, or
+    /// the crate's data directory]
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+    /// Log diagnostics to stderr: -v for info, -vv for debug, -vvv for
+    /// trace. $AOC_LOG sets levels per day, as in `warn,day13=trace`
+    #[arg(long, short, action = ArgAction::Count, global = true)]
+    verbose: u8,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one or every day against an input file
+    Run {
+        /// Day number, or `all` to run every day
+        #[arg(long, short)]
+        day: DaySelection,
+        /// Only run this part; both parts are run when omitted
+        #[arg(long, short, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file name inside data/N, an absolute path to any file, or
+        /// `-` to read stdin
+        #[arg(long, short, default_value = "input.txt")]
+        input: String,
+        /// Set a puzzle parameter, overriding its default and the input's
+        /// config file; may be repeated
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_override)]
+        params: Vec<Override>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// Run every part of every day at once and print a summary table
+    RunAll {
+        /// Input file name inside each data/N
+        #[arg(long, short, default_value = "input.txt")]
+        input: String,
+        /// Worker threads [default: the number of CPUs]
+        #[arg(long, short, value_parser = parse_jobs)]
+        jobs: Option<usize>,
+        #[arg(long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+    },
+    /// List the parameters a day accepts and the values an input uses
+    Params {
+        #[arg(long, short)]
+        day: usize,
+        /// Input file name inside data/N, or an absolute path to any file
+        #[arg(long, short, default_value = "input.txt")]
+        input: String,
+    },
+    /// Check every input file against the expected answers in its sidecar,
+    /// such as data/N/test1.answers.toml
+    Verify {
+        /// Day number, or `all` to verify every day
+        #[arg(long, short, default_value = "all")]
+        day: DaySelection,
+    },
+    /// Load a day's input and step through solving it, showing the state
+    /// as it goes: try `help` at the prompt
+    Repl {
+        #[arg(long, short)]
+        day: usize,
+        /// The part to simulate to begin with
+        #[arg(long, short, default_value_t = 1, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Input file name inside data/N, or an absolute path to any file
+        #[arg(long, short, default_value = "input.txt")]
+        input: String,
+        /// Set a puzzle parameter, overriding its default and the input's
+        /// config file; may be repeated
+        #[arg(long = "param", value_name = "NAME=VALUE", value_parser = parse_override)]
+        params: Vec<Override>,
+    },
+    /// Re-run a day whenever a file in data/N changes, checking the answers
+    /// against the sidecars
+    Watch {
+        #[arg(long, short)]
+        day: usize,
+        /// Only run this part; both parts are run when omitted
+        #[arg(long, short, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file name inside data/N; may be repeated [default: every
+        /// input whose sidecar does not mark it slow]
+        #[arg(long = "input", short)]
+        inputs: Vec<String>,
+        /// Also watch src/dayN.rs, rebuilding and restarting when it changes
+        #[arg(long)]
+        source: bool,
+        /// Milliseconds between looking for changes
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+    /// Time parsing and solving, and compare against a saved baseline
+    Bench {
+        /// Day number, or `all` to benchmark every day
+        #[arg(long, short, default_value = "all")]
+        day: DaySelection,
+        /// Only time this part; both parts are timed when omitted
+        #[arg(long, short, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Input file name inside data/N, or an absolute path to any file
+        #[arg(long, short, default_value = "input.txt")]
+        input: String,
+        /// Number of times to run each stage
+        #[arg(long, short, default_value_t = 10)]
+        runs: usize,
+        /// File holding the baseline timings
+        #[arg(long, default_value = "target/aoc-bench.toml")]
+        baseline: PathBuf,
+        /// Overwrite the baseline with this run's timings
+        #[arg(long)]
+        save: bool,
+        /// Slowdown, in percent of the baseline median, reported as a regression
+        #[arg(long, default_value_t = 20.0)]
+        threshold: f64,
+    },
+    /// Download a day's input to data/N/input.txt, unless it is already
+    /// there. Reads the session token from $AOC_SESSION or `session` in
+    /// data/aoc.toml, and the site from $AOC_BASE_URL or `base_url`
+    Fetch {
+        #[arg(long, short)]
+        day: usize,
+    },
+    /// Post a part's answer to the site, unless data/submissions.jsonl
+    /// shows it was already rejected or the site is rate limiting answers
+    Submit {
+        #[arg(long, short)]
+        day: usize,
+        #[arg(long, short, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: u8,
+        /// Input file name inside data/N, or an absolute path to any file
+        #[arg(long, short, default_value = "input.txt")]
+        input: String,
+        /// Submit this instead of solving, such as the letters drawn by a
+        /// grid answer
+        #[arg(long)]
+        answer: Option<String>,
+    },
+    /// Print a random input for a day
+    Generate {
+        #[arg(long, short)]
+        day: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Roughly the number of entries in the input
+        #[arg(long, default_value_t = 20)]
+        size: usize,
+    },
+    /// Check solutions against simple reference implementations on random
+    /// inputs
+    Fuzz {
+        /// Day number, or `all` to fuzz every day
+        #[arg(long, short, default_value = "all")]
+        day: DaySelection,
+        /// Seed of the first input; each further input uses the next seed
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Number of inputs to check per day
+        #[arg(long, short, default_value_t = 100)]
+        count: u64,
+        /// Roughly the number of entries in each input
+        #[arg(long, default_value_t = 20)]
+        size: usize,
+    },
+    /// Start a new day from src/template.txt
+    NewDay {
+        day: usize,
+        /// Root of the source tree to add the day to
+        #[arg(long, default_value_os_t = scaffold::project_root())]
+        root: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    /// Readable answers
+    Text,
+    /// A JSON array with a record for each part
+    Json,
+    /// A JSON record for each part, one per line
+    Jsonl,
+}
+
+#[derive(Clone, Copy)]
+enum DaySelection {
+    All,
+    Day(usize),
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(DaySelection::All);
+        }
+        s.parse::<usize>()
+            .map(DaySelection::Day)
+            .map_err(|_| "expected `all` or a day number".to_string())
+    }
+}
+
+impl DaySelection {
+    fn days(&self, registry: &Registry) -> Result<Vec<usize>, Box<dyn Error>> {
+        match *self {
+            DaySelection::All => Ok(registry.days().collect()),
+            DaySelection::Day(day) if registry.contains(day) => Ok(vec![day]),
+            DaySelection::Day(day) => Err(format!("Day {day} has no solution").into()),
+        }
+    }
+}
+
+/// A thread count for `--jobs`, which must be at least one
+fn parse_jobs(arg: &str) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(0) => Err("at least one thread is needed".to_string()),
+        Ok(jobs) => Ok(jobs),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn parts(part: Option<u8>) -> Vec<u8> {
+    match part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    }
+}
+
+fn print_answer(day: usize, part: u8, answer: &Answer) {
+    if let Answer::Grid(_) = answer {
+        println!("Day {day}, part {part}:\n{answer}");
+    } else {
+        println!("Day {day}, part {part}: {answer}");
+    }
+}
+
+/// Summarise one part in at most `width` characters for the run-all table
+fn table_cell(result: &PartResult, width: usize) -> String {
+    let text = match &result.outcome {
+        Outcome::Answer(Answer::Grid(_)) => "(grid, below)".to_string(),
+        Outcome::Answer(answer) => answer.to_string(),
+        Outcome::Error(_) => "ERROR".to_string(),
+        Outcome::Panic(_) => "PANIC".to_string(),
+    };
+    match text.chars().count() > width {
+        true => {
+            text.chars()
+                .take(width.saturating_sub(1))
+                .collect::<String>()
+                + "…"
+        }
+        false => text,
+    }
+}
+
+fn run_all(
+    registry: &Registry,
+    input: &str,
+    jobs: usize,
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    let days = registry.days().collect::<Vec<usize>>();
+    let start = Instant::now();
+    let results = parallel::run_all(registry, &days, &[1, 2], input, jobs);
+    let elapsed = start.elapsed();
+    match format {
+        Format::Text => {}
+        Format::Json => return print_json(&results, input).and(check_results(&results)),
+        Format::Jsonl => {
+            for result in &results {
+                print_json_line(result, input)?;
+            }
+            return check_results(&results);
+        }
+    }
+
+    println!(
+        "{:>3}  {:<16} {:>12}  {:<16} {:>12}",
+        "Day", "Part 1", "Time", "Part 2", "Time"
+    );
+    for day_results in results.chunk_by(|a, b| a.day == b.day) {
+        let mut line = format!("{:>3}", day_results[0].day);
+        for result in day_results {
+            line.push_str(&format!(
+                "  {:<16} {:>12}",
+                table_cell(result, 16),
+                format_duration(result.elapsed)
+            ));
+        }
+        println!("{line}");
+    }
+
+    let mut failed = 0;
+    for result in &results {
+        let heading = format!("Day {}, part {}", result.day, result.part);
+        match &result.outcome {
+            Outcome::Answer(answer @ Answer::Grid(_)) => println!("\n{heading}:\n{answer}"),
+            Outcome::Answer(_) => {}
+            Outcome::Error(e) => println!("\n{heading} failed: {e}"),
+            Outcome::Panic(message) => println!("\n{heading} panicked: {message}"),
+        }
+        if !result.succeeded() {
+            failed += 1;
+        }
+    }
+    println!(
+        "\nSolved {} of {} parts in {} on {jobs} threads",
+        results.len() - failed,
+        results.len(),
+        format_duration(elapsed)
+    );
+    match failed {
+        0 => Ok(()),
+        _ => Err(format!("{failed} parts failed").into()),
+    }
+}
+
+fn print_params(solver: &dyn Solver) -> Result<(), Box<dyn Error>> {
+    let params = solver.params()?;
+    for param in solver.declared_params() {
+        let value = params.get::<i64>(param.name)?;
+        println!(
+            "{} = {value}  (default {}) {}",
+            param.name, param.default, param.help
+        );
+    }
+    if solver.declared_params().is_empty() {
+        println!("Day {} has no parameters", solver.day());
+    }
+    Ok(())
+}
+
+fn print_result(result: &PartResult) -> Result<(), Box<dyn Error>> {
+    match &result.outcome {
+        Outcome::Answer(answer) => {
+            print_answer(result.day, result.part, answer);
+            Ok(())
+        }
+        Outcome::Error(e) => Err(e.to_string().into()),
+        Outcome::Panic(message) => Err(format!(
+            "Day {}, part {} panicked: {message}",
+            result.day, result.part
+        )
+        .into()),
+    }
+}
+
+fn print_json(results: &[PartResult], input: &str) -> Result<(), Box<dyn Error>> {
+    let records = results
+        .iter()
+        .map(|result| Record::new(input, result))
+        .collect::<Vec<Record>>();
+    // writeln! rather than println!, so a closed pipe is an error, not a panic
+    writeln!(io::stdout(), "{}", serde_json::to_string_pretty(&records)?)?;
     Ok(())
 }
+
+fn print_json_line(result: &PartResult, input: &str) -> Result<(), Box<dyn Error>> {
+    let record = serde_json::to_string(&Record::new(input, result))?;
+    writeln!(io::stdout(), "{record}")?;
+    Ok(())
+}
+
+/// Fail when any of `results` has no answer, once machine-readable output
+/// has reported them all
+fn check_results(results: &[PartResult]) -> Result<(), Box<dyn Error>> {
+    match results.iter().filter(|r| !r.succeeded()).count() {
+        0 => Ok(()),
+        failed => Err(format!("{failed} parts failed").into()),
+    }
+}
+
+fn run(
+    registry: &Registry,
+    day: DaySelection,
+    part: Option<u8>,
+    input: &str,
+    params: &[Override],
+    format: Format,
+) -> Result<(), Box<dyn Error>> {
+    // Stdin can only be read once, so every selected day gets the same text
+    let stdin = match input {
+        "-" => Some(io::read_to_string(io::stdin().lock())?),
+        _ => None,
+    };
+    let mut results = vec![];
+    for day in day.days(registry)? {
+        let source = match &stdin {
+            Some(text) => Source::Text(text.clone()),
+            None => Source::file(day, input),
+        };
+        let Some(solver) = registry.solver_with(day, source, params) else {
+            continue;
+        };
+        for &part in &parts(part) {
+            let result = solve_part(solver.as_ref(), part);
+            match format {
+                // Report the failure and carry on; check_results fails the run
+                Format::Text => {
+                    if let Err(e) = print_result(&result) {
+                        eprintln!("error: {e}");
+                    }
+                }
+                Format::Jsonl => print_json_line(&result, input)?,
+                Format::Json => {}
+            }
+            results.push(result);
+        }
+    }
+    if format == Format::Json {
+        print_json(&results, input)?;
+    }
+    check_results(&results)
+}
+
+const GREEN: &str = "32";
+const YELLOW: &str = "33";
+const RED: &str = "31";
+
+/// Colour `text` with an ANSI SGR `code`, if stdout is a terminal and
+/// $NO_COLOR is unset
+fn paint(text: &str, code: &str) -> String {
+    if io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none() {
+        format!("\x1b[{code}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
+
+fn print_check(check: &Check) {
+    let label = format!("Day {}, {}, part {}", check.day, check.file, check.part);
+    match (&check.status, &check.answer) {
+        (Status::Pass, _) => println!("{label}: {}", paint("pass", GREEN)),
+        (Status::Missing, Some(answer)) => {
+            println!(
+                "{label}: {} (got {})",
+                paint("missing expected answer", YELLOW),
+                one_line(answer)
+            )
+        }
+        (Status::Fail { expected }, Some(answer)) => println!(
+            "{label}: {}, got {} but expected {}",
+            paint("FAIL", RED),
+            one_line(answer),
+            one_line(expected)
+        ),
+        (Status::Error(e), _) => println!("{label}: {}, {e}", paint("ERROR", RED)),
+        (_, None) => println!("{label}: no answer"),
+    }
+}
+
+/// Render an answer on a single line, so grids don't break up the report
+fn one_line(answer: &Answer) -> String {
+    answer.to_string().replace('\n', "\\n")
+}
+
+fn verify(registry: &Registry, day: DaySelection) -> Result<(), Box<dyn Error>> {
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for day in day.days(registry)? {
+        for check in verify_day(registry, day)? {
+            print_check(&check);
+            match check.status {
+                Status::Pass => passed += 1,
+                Status::Missing => missing += 1,
+                Status::Fail { .. } | Status::Error(_) => failed += 1,
+            }
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if failed > 0 {
+        return Err(format!("{failed} checks failed").into());
+    }
+    Ok(())
+}
+
+struct WatchOptions {
+    part: Option<u8>,
+    inputs: Vec<String>,
+    source: bool,
+    interval: u64,
+}
+
+/// Check `parts` of each of `inputs` against the sidecars as they are now
+fn check_inputs(
+    registry: &Registry,
+    day: usize,
+    parts: &[u8],
+    inputs: &[String],
+) -> Result<(), Box<dyn Error>> {
+    let manifest = Manifest::load(day)?;
+    let inputs = match inputs {
+        [] => quick_inputs(day)?,
+        inputs => inputs.to_vec(),
+    };
+    for file in inputs {
+        let Some(solver) = registry.solver(day, &file) else {
+            continue;
+        };
+        for &part in parts {
+            print_check(&verify::check(solver.as_ref(), &manifest, &file, part));
+        }
+    }
+    Ok(())
+}
+
+/// Rebuild this binary, then replace this process with the new one. Returns
+/// if the build fails.
+fn restart(exe: &Path) -> Result<(), Box<dyn Error>> {
+    let mut build = process::Command::new(env::var_os("CARGO").unwrap_or("cargo".into()));
+    build.args(["build", "--quiet", "--bin", "aoc"]);
+    if !cfg!(debug_assertions) {
+        build.arg("--release");
+    }
+    if !build
+        .current_dir(scaffold::project_root())
+        .status()?
+        .success()
+    {
+        println!("{}", paint("Build failed; waiting for another change", RED));
+        return Ok(());
+    }
+    let mut command = process::Command::new(exe);
+    command.args(env::args_os().skip(1));
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(command.exec().into())
+    }
+    #[cfg(not(unix))]
+    process::exit(command.status()?.code().unwrap_or(1))
+}
+
+fn watch(registry: &Registry, day: usize, options: WatchOptions) -> Result<(), Box<dyn Error>> {
+    if !registry.contains(day) {
+        return Err(format!("Day {day} has no solution").into());
+    }
+    // Looked up before any rebuild replaces the file it names
+    let exe = env::current_exe()?;
+    let source = scaffold::project_root()
+        .join("src")
+        .join(format!("day{day}.rs"));
+    let mut watcher = Watcher::new(
+        vec![solvable::data_dir(day)],
+        if options.source {
+            vec![source.clone()]
+        } else {
+            vec![]
+        },
+    );
+    let parts = parts(options.part);
+    let mut changed = vec![];
+    loop {
+        if changed.contains(&source) {
+            println!("\n{} changed, rebuilding", source.display());
+            restart(&exe)?;
+        } else {
+            if !changed.is_empty() {
+                let names = changed
+                    .iter()
+                    .filter_map(|path| path.file_name()?.to_str())
+                    .collect::<Vec<&str>>();
+                println!("\n{} changed", names.join(", "));
+            }
+            if let Err(e) = check_inputs(registry, day, &parts, &options.inputs) {
+                println!("{}: {e}", paint("ERROR", RED));
+            }
+            println!("Watching for changes; press Ctrl-C to stop");
+        }
+        changed = vec![];
+        while changed.is_empty() {
+            thread::sleep(Duration::from_millis(options.interval));
+            changed = watcher.poll();
+        }
+    }
+}
+
+struct BenchOptions {
+    part: Option<u8>,
+    input: String,
+    runs: usize,
+    baseline: PathBuf,
+    save: bool,
+    threshold: f64,
+}
+
+fn bench(
+    registry: &Registry,
+    day: DaySelection,
+    options: BenchOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut baseline = Baseline::load(&options.baseline)?;
+    let mut regressions = 0;
+    println!(
+        "{:>3}  {:<12} {:<6} {:>12} {:>12} {:>12}",
+        "Day", "Input", "Stage", "Min", "Median", "Max"
+    );
+    for day in day.days(registry)? {
+        for m in bench_day(
+            registry,
+            day,
+            &options.input,
+            &parts(options.part),
+            options.runs,
+        )? {
+            let mut line = format!(
+                "{:>3}  {:<12} {:<6} {:>12} {:>12} {:>12}",
+                m.day,
+                m.input,
+                m.stage.to_string(),
+                format_duration(m.timing.min),
+                format_duration(m.timing.median),
+                format_duration(m.timing.max)
+            );
+            if let Some(change) = baseline.regression(&m, options.threshold / 100.0) {
+                regressions += 1;
+                line.push_str(&format!("  REGRESSION +{:.0}%", change * 100.0));
+            }
+            println!("{line}");
+            if options.save {
+                baseline.record(&m);
+            }
+        }
+    }
+    if options.save {
+        baseline.save(&options.baseline)?;
+        println!("Saved baseline to {}", options.baseline.display());
+    } else if regressions > 0 {
+        println!("{regressions} stages regressed against the baseline");
+    }
+    Ok(())
+}
+
+fn fetch(day: usize) -> Result<(), Box<dyn Error>> {
+    let client = Client::from_env(Config::load(&client::config_path())?);
+    let path = solvable::data_dir(day).join("input.txt");
+    match client::fetch(&client, day, &path)? {
+        Fetched::Cached => println!("Already have {}", path.display()),
+        Fetched::Downloaded => println!("Wrote {}", path.display()),
+    }
+    Ok(())
+}
+
+fn submit(
+    registry: &Registry,
+    day: usize,
+    part: u8,
+    input: &str,
+    answer: Option<String>,
+) -> Result<(), Box<dyn Error>> {
+    let answer = match answer {
+        Some(answer) => Answer::Text(answer),
+        None => {
+            let solver = registry
+                .solver(day, input)
+                .ok_or_else(|| format!("Day {day} has no solution"))?;
+            let result = solve_part(solver.as_ref(), part);
+            match result.outcome {
+                Outcome::Answer(answer) => answer,
+                _ => return print_result(&result),
+            }
+        }
+    };
+    let client = Client::from_env(Config::load(&client::config_path())?);
+    let mut log = SubmissionLog::load(&submit::log_path())?;
+    let attempt = submit::submit(&client, &mut log, day, part, &answer)?;
+    println!("Day {day}, part {part}: submitted {}", attempt.answer);
+    println!("{}", attempt.message);
+    match attempt.verdict {
+        Verdict::Correct => Ok(()),
+        _ => Err(format!("{} was not accepted", attempt.answer).into()),
+    }
+}
+
+fn print_generated(day: usize, knobs: Knobs) -> Result<(), Box<dyn Error>> {
+    let generated = generate(day, knobs).ok_or_else(|| format!("Day {day} has no generator"))?;
+    write!(io::stdout(), "{}", generated.text)?;
+    if !generated.params.is_empty() {
+        let flags = generated
+            .params
+            .iter()
+            .map(|(name, value)| format!("--param {name}={value}"))
+            .collect::<Vec<String>>();
+        eprintln!("Solve with {}", flags.join(" "));
+    }
+    Ok(())
+}
+
+fn fuzz(
+    registry: &Registry,
+    day: DaySelection,
+    first_seed: u64,
+    count: u64,
+    size: usize,
+) -> Result<(), Box<dyn Error>> {
+    let (mut checked, mut failed) = (0, 0);
+    for day in day.days(registry)? {
+        for seed in first_seed..first_seed + count {
+            let Some(comparisons) = compare(registry, day, Knobs { seed, size }) else {
+                continue;
+            };
+            for c in comparisons {
+                checked += 1;
+                if c.agrees() {
+                    continue;
+                }
+                failed += 1;
+                let got = match &c.result.outcome {
+                    Outcome::Answer(answer) => one_line(answer),
+                    Outcome::Error(e) => format!("error {e}"),
+                    Outcome::Panic(message) => format!("panic {message}"),
+                };
+                println!(
+                    "Day {day}, part {}, seed {seed}: FAIL, got {got} but expected {} \
+                     (aoc generate --day {day} --seed {seed} --size {size})",
+                    c.result.part,
+                    one_line(&c.expected)
+                );
+            }
+        }
+    }
+    println!(
+        "{} of {checked} parts agreed with the reference",
+        checked - failed
+    );
+    if failed > 0 {
+        return Err(format!("{failed} parts disagreed").into());
+    }
+    Ok(())
+}
+
+fn execute(cli: Cli) -> Result<(), Box<dyn Error>> {
+    let filter = match env::var(LOG_VAR) {
+        Ok(spec) => Filter::from_str(&spec).map_err(|e| format!("{LOG_VAR}: {e}"))?,
+        Err(_) => Filter::quiet(),
+    };
+    logging::init(filter.with_verbosity(cli.verbose))?;
+    if let Some(dir) = cli.data_dir {
+        solvable::set_data_root(dir);
+    }
+    let registry = Registry::default();
+    match cli.command {
+        Command::Run {
+            day,
+            part,
+            input,
+            params,
+            format,
+        } => run(&registry, day, part, &input, &params, format),
+        Command::RunAll {
+            input,
+            jobs,
+            format,
+        } => {
+            let jobs = match jobs {
+                Some(jobs) => jobs,
+                None => thread::available_parallelism()?.get(),
+            };
+            run_all(&registry, &input, jobs, format)
+        }
+        Command::Params { day, input } => {
+            let solver = registry
+                .solver(day, &input)
+                .ok_or_else(|| format!("Day {day} has no solution"))?;
+            print_params(solver.as_ref())
+        }
+        Command::Verify { day } => verify(&registry, day),
+        Command::Repl {
+            day,
+            part,
+            input,
+            params,
+        } => {
+            let solver = registry
+                .solver_with(day, Source::file(day, &input), &params)
+                .ok_or_else(|| format!("Day {day} has no solution"))?;
+            solver.parse()?;
+            Ok(repl::run(
+                solver.as_ref(),
+                part,
+                io::stdin().lock(),
+                io::stdout().lock(),
+            )?)
+        }
+        Command::Watch {
+            day,
+            part,
+            inputs,
+            source,
+            interval,
+        } => watch(
+            &registry,
+            day,
+            WatchOptions {
+                part,
+                inputs,
+                source,
+                interval,
+            },
+        ),
+        Command::Bench {
+            day,
+            part,
+            input,
+            runs,
+            baseline,
+            save,
+            threshold,
+        } => bench(
+            &registry,
+            day,
+            BenchOptions {
+                part,
+                input,
+                runs,
+                baseline,
+                save,
+                threshold,
+            },
+        ),
+        Command::Fetch { day } => fetch(day),
+        Command::Submit {
+            day,
+            part,
+            input,
+            answer,
+        } => submit(&registry, day, part, &input, answer),
+        Command::Generate { day, seed, size } => print_generated(day, Knobs { seed, size }),
+        Command::Fuzz {
+            day,
+            seed,
+            count,
+            size,
+        } => fuzz(&registry, day, seed, count, size),
+        Command::NewDay { day, root } => {
+            for path in scaffold::new_day(&root, day)? {
+                println!("Wrote {}", path.display());
+            }
+            Ok(())
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match execute(Cli::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_truncates_table_cells_by_character() {
+        let result = |text: &str| PartResult {
+            day: 1,
+            part: 1,
+            outcome: Outcome::Answer(Answer::Text(text.to_string())),
+            elapsed: Duration::ZERO,
+        };
+        assert_eq!(table_cell(&result("ÅÅÅÅÅ"), 5), "ÅÅÅÅÅ");
+        assert_eq!(table_cell(&result("ÅÅÅÅÅÅ"), 5), "ÅÅÅÅ…");
+    }
+}
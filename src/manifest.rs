@@ -0,0 +1,195 @@
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::solvable::data_dir;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+const SIDECAR_EXTENSION: &str = "answers.toml";
+
+/// Sidecar holding the expected answers for an input file: `test1.txt` is
+/// checked against `test1.answers.toml` beside it.
+///
+/// ```toml
+/// part1 = 95437
+/// part2 = 24933642
+/// # Skipped by `cargo test` unless run with --ignored
+/// slow = true
+/// ```
+pub fn answers_path(input: &Path) -> PathBuf {
+    input.with_extension(SIDECAR_EXTENSION)
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExpectedValue {
+    Integer(i64),
+    Text(String),
+}
+
+impl From<ExpectedValue> for Answer {
+    fn from(value: ExpectedValue) -> Self {
+        match value {
+            ExpectedValue::Integer(n) => Answer::from(n),
+            ExpectedValue::Text(s) => Answer::from(s),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    part1: Option<ExpectedValue>,
+    part2: Option<ExpectedValue>,
+    #[serde(default)]
+    slow: bool,
+}
+
+/// The expected answers for one input file
+pub struct Expected {
+    answers: [Option<Answer>; 2],
+    /// Too slow to check on every test run
+    pub slow: bool,
+}
+
+impl Expected {
+    pub fn parse(text: &str) -> Result<Self, AocError> {
+        let entry: Entry = toml::from_str(text).map_err(|e| {
+            let offset = e.span().map_or(0, |span| span.start);
+            AocError::parse_at_offset(text, offset, e.message())
+        })?;
+        Ok(Expected {
+            answers: [entry.part1.map(Answer::from), entry.part2.map(Answer::from)],
+            slow: entry.slow,
+        })
+    }
+
+    pub fn part(&self, part: u8) -> Option<&Answer> {
+        self.answers
+            .get(usize::from(part).checked_sub(1)?)?
+            .as_ref()
+    }
+}
+
+/// Expected answers for each input file of a day, gathered from the
+/// sidecars in `data/N`
+#[derive(Default)]
+pub struct Manifest {
+    entries: BTreeMap<String, Expected>,
+}
+
+impl Manifest {
+    /// Load the sidecars for `day`. A day without any has no expectations.
+    pub fn load(day: usize) -> Result<Self, AocError> {
+        Self::load_from(day, &data_dir(day))
+    }
+
+    pub fn load_from(day: usize, dir: &Path) -> Result<Self, AocError> {
+        let suffix = format!(".{SIDECAR_EXTENSION}");
+        let mut manifest = Manifest::default();
+        for name in file_names(day, dir)? {
+            let Some(stem) = name.strip_suffix(&suffix) else {
+                continue;
+            };
+            let path = dir.join(&name);
+            let text = fs::read_to_string(&path).map_err(|source| AocError::Io {
+                day,
+                path: path.clone(),
+                source,
+            })?;
+            let expected = Expected::parse(&text).map_err(|e| e.with_context(day, &path))?;
+            manifest.entries.insert(format!("{stem}.txt"), expected);
+        }
+        Ok(manifest)
+    }
+
+    pub fn get(&self, file: &str) -> Option<&Expected> {
+        self.entries.get(file)
+    }
+
+    pub fn expected(&self, file: &str, part: u8) -> Option<&Answer> {
+        self.get(file)?.part(part)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+}
+
+/// Sorted names of the files in `dir`, or none if it does not exist
+fn file_names(day: usize, dir: &Path) -> Result<Vec<String>, AocError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(source) => {
+            return Err(AocError::Io {
+                day,
+                path: dir.to_path_buf(),
+                source,
+            })
+        }
+    };
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect::<Vec<String>>();
+    names.sort();
+    Ok(names)
+}
+
+/// Names of the puzzle inputs (`*.txt`) in the data directory of `day`
+pub fn input_files(day: usize) -> Result<Vec<String>, AocError> {
+    let mut files = file_names(day, &data_dir(day))?;
+    files.retain(|name| name.ends_with(".txt"));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_reads_integer_and_text_answers() -> Result<(), AocError> {
+        let expected = Expected::parse("part1 = 24000\npart2 = \"CMZ\"\n")?;
+        assert_eq!(expected.part(1), Some(&Answer::from(24000)));
+        assert_eq!(expected.part(2), Some(&Answer::from("CMZ")));
+        assert_eq!(expected.part(3), None);
+        assert!(!expected.slow);
+
+        let expected = Expected::parse("part1 = 7\nslow = true\n")?;
+        assert_eq!(expected.part(2), None);
+        assert!(expected.slow);
+        Ok(())
+    }
+
+    #[test]
+    fn it_reports_where_a_sidecar_is_malformed() {
+        match Expected::parse("part1 = 1\npart3 = 1\n") {
+            Err(AocError::Parse { line, .. }) => assert_eq!(line, 2),
+            _ => panic!("Expected a parse error"),
+        }
+    }
+
+    #[test]
+    fn it_gathers_the_sidecars_of_a_day() -> Result<(), AocError> {
+        let manifest = Manifest::load(9)?;
+        assert_eq!(
+            manifest.files().collect::<Vec<&str>>(),
+            vec!["input.txt", "test1.txt", "test2.txt"]
+        );
+        assert_eq!(manifest.expected("test2.txt", 2), Some(&Answer::from(36)));
+        assert_eq!(manifest.expected("test3.txt", 1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn it_has_a_manifest_for_every_registered_day() -> Result<(), AocError> {
+        for day in crate::registry::Registry::default().days() {
+            let manifest = Manifest::load(day)?;
+            assert!(manifest.expected("test1.txt", 1).is_some(), "Day {day}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use crate::error::AocError;
+use crate::registry::Registry;
+use crate::report::{solve_part, Outcome, PartResult};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Solve each of `parts` for `day` with one solver, so its input is read and
+/// parsed once
+fn solve(registry: &Registry, day: usize, parts: &[u8], input: &str) -> Vec<PartResult> {
+    match registry.solver(day, input) {
+        Some(solver) => parts
+            .iter()
+            .map(|&part| solve_part(solver.as_ref(), part))
+            .collect(),
+        None => parts
+            .iter()
+            .map(|&part| PartResult {
+                day,
+                part,
+                outcome: Outcome::Error(
+                    AocError::unsolvable("Day is not registered").with_day(day),
+                ),
+                elapsed: Duration::ZERO,
+            })
+            .collect(),
+    }
+}
+
+/// Solve every part of every one of `days` against `input` on a pool of
+/// `threads` workers, a day at a time. A part that fails or panics is
+/// reported in its result without stopping the others. Results are in day
+/// and part order.
+pub fn run_all(
+    registry: &Registry,
+    days: &[usize],
+    parts: &[u8],
+    input: &str,
+    threads: usize,
+) -> Vec<PartResult> {
+    // Each worker builds its own solvers, since solvers are not Send
+    let jobs = Mutex::new(days.iter());
+    let results = Mutex::new(vec![]);
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            scope.spawn(|| loop {
+                let Some(&day) = jobs.lock().unwrap().next() else {
+                    break;
+                };
+                let solved = solve(registry, day, parts, input);
+                results.lock().unwrap().extend(solved);
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|r| (r.day, r.part));
+    results
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::answer::Answer;
+    use crate::params::Params;
+    use crate::solvable::{Solvable, Source};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static PARSES: AtomicUsize = AtomicUsize::new(0);
+
+    struct Panics(Source);
+
+    impl Solvable<99> for Panics {
+        type Input = ();
+
+        fn from_source(source: Source) -> Self {
+            Panics(source)
+        }
+        fn source(&self) -> &Source {
+            &self.0
+        }
+        fn parse(_: &str) -> Result<(), AocError> {
+            PARSES.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+        fn part1(&self, _: &(), _: &Params) -> Result<Answer, AocError> {
+            panic!("Part 1 exploded")
+        }
+        fn part2(&self, _: &(), _: &Params) -> Result<Answer, AocError> {
+            Ok(2.into())
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day6"))]
+    fn it_reports_answers_in_order() {
+        let results = run_all(&Registry::default(), &[6, 1], &[1, 2], "test1.txt", 3);
+        let order = results
+            .iter()
+            .map(|r| (r.day, r.part))
+            .collect::<Vec<(usize, u8)>>();
+        assert_eq!(order, [(1, 1), (1, 2), (6, 1), (6, 2)]);
+        assert!(results.iter().all(PartResult::succeeded));
+    }
+
+    #[test]
+    fn it_catches_panics_and_errors() {
+        let mut registry = Registry::new();
+        registry.register::<99, Panics>();
+        // An absolute path, since day 99 has no data directory
+        let input = crate::solvable::data_dir(1).join("test1.txt");
+        let input = input.to_str().unwrap();
+        let results = run_all(&registry, &[99, 100], &[1, 2], input, 2);
+        assert!(matches!(&results[0].outcome, Outcome::Panic(m) if m == "Part 1 exploded"));
+        assert!(matches!(&results[1].outcome, Outcome::Answer(a) if a == &2));
+        assert!(matches!(results[2].outcome, Outcome::Error(_)));
+        assert!(matches!(results[3].outcome, Outcome::Error(_)));
+        assert_eq!(
+            PARSES.load(Ordering::SeqCst),
+            1,
+            "Parsed once for both parts"
+        );
+    }
+}
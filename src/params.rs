@@ -0,0 +1,165 @@
+use crate::error::AocError;
+use crate::solvable::Source;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// A named integer setting a puzzle declares, such as the row day 15
+/// inspects, which differs between the example and the real input.
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+    pub help: &'static str,
+}
+
+/// A value given for a parameter on the command line, as `name=value`
+pub type Override = (String, i64);
+
+/// Parse a `name=value` command line argument
+pub fn parse_override(arg: &str) -> Result<Override, String> {
+    let (name, value) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("Expected name=value, found \"{arg}\""))?;
+    let value = value
+        .trim()
+        .parse::<i64>()
+        .map_err(|e| format!("Invalid value for {name}: {e}"))?;
+    Ok((name.trim().to_string(), value))
+}
+
+/// Config file holding the parameters for an input file: `test1.txt` is
+/// configured by `test1.params.toml` beside it.
+///
+/// ```toml
+/// row = 10
+/// limit = 20
+/// ```
+pub fn config_path(input: &Path) -> PathBuf {
+    input.with_extension("params.toml")
+}
+
+/// The parameter values a puzzle is solved with
+#[derive(Clone, Debug, Default)]
+pub struct Params {
+    values: BTreeMap<String, i64>,
+}
+
+impl Params {
+    /// Start from the defaults `day` declares, then apply the config file
+    /// for `source`, if it has one, then `overrides`.
+    pub fn resolve(
+        day: usize,
+        declared: &[Param],
+        source: &Source,
+        overrides: &[Override],
+    ) -> Result<Self, AocError> {
+        let mut params = Params {
+            values: declared
+                .iter()
+                .map(|p| (p.name.to_string(), p.default))
+                .collect(),
+        };
+        if let Source::File(input) = source {
+            let path = config_path(input);
+            for (name, value) in read_config(day, &path)? {
+                params.set(&name, value)?;
+            }
+        }
+        for (name, value) in overrides {
+            params.set(name, *value)?;
+        }
+        Ok(params)
+    }
+
+    fn set(&mut self, name: &str, value: i64) -> Result<(), AocError> {
+        match self.values.get_mut(name) {
+            Some(v) => {
+                *v = value;
+                Ok(())
+            }
+            None => Err(AocError::param(name, "No such parameter")),
+        }
+    }
+
+    /// The value of `name` as a `T`
+    pub fn get<T: TryFrom<i64>>(&self, name: &str) -> Result<T, AocError> {
+        let value = *self
+            .values
+            .get(name)
+            .ok_or_else(|| AocError::param(name, "No such parameter"))?;
+        T::try_from(value).map_err(|_| AocError::param(name, format!("{value} is out of range")))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, i64)> {
+        self.values
+            .iter()
+            .map(|(name, &value)| (name.as_str(), value))
+    }
+}
+
+/// The values set in the config file at `path`, if there is one
+pub(crate) fn read_config(day: usize, path: &Path) -> Result<BTreeMap<String, i64>, AocError> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(source) => {
+            return Err(AocError::Io {
+                day,
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+    };
+    toml::from_str(&text).map_err(|e| {
+        let offset = e.span().map_or(0, |span| span.start);
+        AocError::parse_at_offset(&text, offset, e.message()).with_context(day, path)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DECLARED: &[Param] = &[
+        Param {
+            name: "row",
+            default: 2_000_000,
+            help: "",
+        },
+        Param {
+            name: "limit",
+            default: 4_000_000,
+            help: "",
+        },
+    ];
+
+    #[test]
+    fn it_parses_overrides() {
+        assert_eq!(parse_override("row=10"), Ok(("row".to_string(), 10)));
+        assert!(parse_override("row").is_err());
+        assert!(parse_override("row=ten").is_err());
+    }
+
+    #[test]
+    fn it_applies_config_then_overrides_over_defaults() -> Result<(), AocError> {
+        let defaults = Params::resolve(15, DECLARED, &Source::Text(String::new()), &[])?;
+        assert_eq!(defaults.get::<i32>("row")?, 2_000_000);
+
+        let test1 = Source::file(15, "test1.txt");
+        let params = Params::resolve(15, DECLARED, &test1, &[("limit".to_string(), 7)])?;
+        assert_eq!(params.get::<i32>("row")?, 10);
+        assert_eq!(params.get::<i32>("limit")?, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn it_rejects_unknown_and_out_of_range_parameters() {
+        let text = Source::Text(String::new());
+        let unknown = Params::resolve(15, DECLARED, &text, &[("rows".to_string(), 1)]);
+        assert!(matches!(unknown, Err(AocError::Param { .. })));
+
+        let negative = Params::resolve(15, DECLARED, &text, &[("row".to_string(), -1)]);
+        assert!(negative.and_then(|p| p.get::<usize>("row")).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use crate::error::AocError;
+use crate::grid::Grid;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// A line of puzzle input, remembering where it came from so errors can
+/// point back at it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    /// 1-based line number within the whole input
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// An error pointing at `token`, which should be a slice of this line;
+    /// otherwise it points at the start of the line
+    pub fn error_at(&self, token: &str, reason: impl Into<String>) -> AocError {
+        AocError::parse_at(self.number, self.text, token, reason)
+    }
+
+    /// An error about the line as a whole
+    pub fn error(&self, reason: impl Into<String>) -> AocError {
+        AocError::parse(self.number, 1, reason)
+    }
+
+    /// Parse `token`, a slice of this line, describing it as `what` if it
+    /// is invalid
+    pub fn value<T>(&self, token: &str, what: &str) -> Result<T, AocError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        token
+            .parse()
+            .map_err(|e| self.error_at(token, format!("Invalid {what} \"{token}\": {e}")))
+    }
+
+    /// The two sides of the first `separator`
+    pub fn split_once(&self, separator: &str) -> Result<(&'a str, &'a str), AocError> {
+        self.text
+            .split_once(separator)
+            .ok_or_else(|| self.error(format!("Expected \"{separator}\" in \"{}\"", self.text)))
+    }
+
+    /// The key and value of a `key: value` line
+    pub fn key_value(&self) -> Result<(&'a str, &'a str), AocError> {
+        let (key, value) = self.split_once(":")?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /// Every integer in the line, in order, ignoring whatever surrounds them.
+    /// A `-` before the digits makes it negative, unless it follows another
+    /// number, as in the range `2-4`.
+    pub fn integers<T>(&self) -> Result<Vec<T>, AocError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut integers = vec![];
+        let mut i = 0;
+        while i < bytes.len() {
+            let negative = bytes[i] == b'-'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+                && !(i > 0 && bytes[i - 1].is_ascii_digit());
+            if !negative && !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            i += 1;
+            while bytes.get(i).is_some_and(u8::is_ascii_digit) {
+                i += 1;
+            }
+            integers.push(self.value(&self.text[start..i], "number")?);
+        }
+        Ok(integers)
+    }
+
+    /// Match the line against `template`, in which each `{}` stands for a
+    /// non-empty value, returning the values in order. The text between
+    /// them must match exactly.
+    ///
+    /// ```
+    /// # use adventofcode2022::parse::lines;
+    /// let line = lines("move 3 from 1 to 2").next().unwrap();
+    /// assert_eq!(line.template("move {} from {} to {}").unwrap(), ["3", "1", "2"]);
+    /// ```
+    pub fn template<const N: usize>(&self, template: &str) -> Result<[&'a str; N], AocError> {
+        let literals = template.split("{}").collect::<Vec<&str>>();
+        assert_eq!(literals.len(), N + 1, "{template:?} must have {N} {{}}s");
+        let mut rest = self.text;
+        let mut values = [""; N];
+        for (i, literal) in literals.iter().enumerate() {
+            let found = match i {
+                0 => rest.starts_with(literal).then_some(0),
+                _ if literal.is_empty() && i == N => Some(rest.len()),
+                _ => rest.find(literal),
+            };
+            let Some(at) = found else {
+                return Err(self.error_at(
+                    rest,
+                    format!("Expected \"{template}\", missing \"{literal}\""),
+                ));
+            };
+            if i > 0 {
+                if at == 0 {
+                    return Err(self.error_at(rest, format!("Expected a value for \"{template}\"")));
+                }
+                values[i - 1] = &rest[..at];
+            }
+            rest = &rest[at + literal.len()..];
+        }
+        if !rest.is_empty() {
+            return Err(self.error_at(rest, format!("Unexpected \"{rest}\"")));
+        }
+        Ok(values)
+    }
+}
+
+/// The lines of `text`, numbered from 1
+pub fn lines(text: &str) -> impl Iterator<Item = Line<'_>> {
+    text.lines().enumerate().map(|(i, text)| Line {
+        number: i + 1,
+        text,
+    })
+}
+
+/// Apply `parse` to every line, stopping at the first error
+pub fn parse_lines<'a, T>(
+    text: &'a str,
+    parse: impl FnMut(Line<'a>) -> Result<T, AocError>,
+) -> Result<Vec<T>, AocError> {
+    lines(text).map(parse).collect()
+}
+
+/// The runs of lines separated by blank lines, leaving out the blank lines
+pub fn groups(text: &str) -> Vec<Vec<Line<'_>>> {
+    let mut groups = vec![];
+    let mut group = vec![];
+    for line in lines(text) {
+        if line.text.trim().is_empty() {
+            if !group.is_empty() {
+                groups.push(group);
+                group = vec![];
+            }
+        } else {
+            group.push(line);
+        }
+    }
+    if !group.is_empty() {
+        groups.push(group);
+    }
+    groups
+}
+
+/// A map of characters, a line per row, as in [`Grid::parse`]
+pub fn char_grid(text: &str) -> Result<Grid<char>, AocError> {
+    Grid::parse(text, |_, ch| Ok(ch))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn line(text: &str) -> Line<'_> {
+        Line { number: 3, text }
+    }
+
+    #[test]
+    fn it_splits_groups_at_blank_lines() {
+        let groups = groups("1\n2\n\n3\n\n\n4\n");
+        let numbers = groups
+            .iter()
+            .map(|g| g.iter().map(|l| l.number).collect())
+            .collect::<Vec<Vec<usize>>>();
+        assert_eq!(numbers, [vec![1, 2], vec![4], vec![7]]);
+        assert_eq!(groups[2][0].text, "4");
+        assert!(super::groups("").is_empty());
+    }
+
+    #[test]
+    fn it_finds_integers_among_other_text() -> Result<(), AocError> {
+        let sensor = line("Sensor at x=-2, y=18: closest beacon is at x=-2, y=15");
+        assert_eq!(sensor.integers::<i32>()?, [-2, 18, -2, 15]);
+        assert_eq!(line("2-4,6-8").integers::<u32>()?, [2, 4, 6, 8]);
+        let error = line("a 99999").integers::<u8>().unwrap_err().to_string();
+        assert!(
+            error.starts_with("line 3, column 3: Invalid number"),
+            "{error}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn it_matches_templates() -> Result<(), AocError> {
+        let line = line("move 13 from 1 to 9");
+        assert_eq!(line.template("move {} from {} to {}")?, ["13", "1", "9"]);
+        assert_eq!(line.template("move {}")?, ["13 from 1 to 9"]);
+        let error = |template| line.template::<2>(template).unwrap_err().to_string();
+        assert_eq!(
+            error("move {} onto {}"),
+            "line 3, column 6: Expected \"move {} onto {}\", missing \" onto \""
+        );
+        assert_eq!(
+            error("move {} from {} to"),
+            "line 3, column 18: Unexpected \" 9\""
+        );
+        assert_eq!(
+            error("push {} from {}"),
+            "line 3, column 1: Expected \"push {} from {}\", missing \"push \""
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn it_points_at_bad_values() {
+        let line = line("addx 1x");
+        let [x] = line.template("addx {}").unwrap();
+        let error = line.value::<i32>(x, "operand").unwrap_err().to_string();
+        assert!(error.starts_with("line 3, column 6: Invalid operand \"1x\""));
+        assert_eq!(
+            Line {
+                number: 1,
+                text: "Starting items: 79, 98"
+            }
+            .key_value()
+            .unwrap(),
+            ("Starting items", "79, 98")
+        );
+    }
+}
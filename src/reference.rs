@@ -0,0 +1,665 @@
+use crate::answer::Answer;
+use crate::generate::{generate, Generated, Knobs};
+use crate::registry::Registry;
+use crate::report::{solve_part, Outcome, PartResult};
+use crate::solvable::Source;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+
+/// Answers to both parts of `input` for `day` by the most straightforward
+/// method, to check the real solutions against. Assumes the input is well
+/// formed, as generated, and panics otherwise.
+pub fn solve(day: usize, input: &Generated) -> Option<[Answer; 2]> {
+    let text = input.text.as_str();
+    let param = |name: &str, default: i64| {
+        input
+            .params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map_or(default, |&(_, value)| value)
+    };
+    let answers = match day {
+        1 => calories(text),
+        2 => strategy_guide(text),
+        3 => rucksacks(text),
+        4 => section_pairs(text),
+        5 => crate_stacks(text),
+        6 => datastream(text, param("window1", 4), param("window2", 14)),
+        7 => terminal_output(
+            text,
+            param("small_dir", 100_000),
+            param("disk_size", 70_000_000),
+            param("required_space", 30_000_000),
+        ),
+        8 => tree_heights(text),
+        9 => rope_motions(text, param("knots1", 2), param("knots2", 10)),
+        10 => cpu_program(text),
+        11 => monkey_notes(text, param("rounds1", 20), param("rounds2", 10_000)),
+        12 => heightmap(text),
+        13 => packet_pairs(text),
+        14 => rock_paths(text),
+        15 => sensor_report(text, param("row", 2_000_000), param("limit", 4_000_000)),
+        _ => return None,
+    };
+    Some(answers)
+}
+
+fn numbers(line: &str) -> Vec<i64> {
+    line.split(|c: char| c != '-' && !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect()
+}
+
+fn calories(text: &str) -> [Answer; 2] {
+    let mut totals = text
+        .split("\n\n")
+        .map(|elf| elf.lines().map(|l| l.parse::<u64>().unwrap()).sum())
+        .collect::<Vec<u64>>();
+    totals.sort_unstable_by(|a, b| b.cmp(a));
+    [totals[0].into(), totals.iter().take(3).sum::<u64>().into()]
+}
+
+fn strategy_guide(text: &str) -> [Answer; 2] {
+    // Scores indexed by the opponent's shape, then the second column
+    const AS_SHAPE: [[u32; 3]; 3] = [[4, 8, 3], [1, 5, 9], [7, 2, 6]];
+    const AS_OUTCOME: [[u32; 3]; 3] = [[3, 4, 8], [1, 5, 9], [2, 6, 7]];
+    let rounds = text
+        .lines()
+        .map(|l| {
+            let b = l.as_bytes();
+            (usize::from(b[0] - b'A'), usize::from(b[2] - b'X'))
+        })
+        .collect::<Vec<(usize, usize)>>();
+    let score = |table: &[[u32; 3]; 3]| rounds.iter().map(|&(a, b)| table[a][b]).sum::<u32>();
+    [score(&AS_SHAPE).into(), score(&AS_OUTCOME).into()]
+}
+
+fn priority(item: char) -> usize {
+    ('a'..='z')
+        .chain('A'..='Z')
+        .position(|c| c == item)
+        .unwrap()
+        + 1
+}
+
+fn rucksacks(text: &str) -> [Answer; 2] {
+    let lines = text.lines().collect::<Vec<&str>>();
+    let part1 = lines
+        .iter()
+        .map(|l| {
+            let (first, second) = l.split_at(l.len() / 2);
+            priority(first.chars().find(|&c| second.contains(c)).unwrap())
+        })
+        .sum::<usize>();
+    let part2 = lines
+        .chunks(3)
+        .map(|g| {
+            priority(
+                g[0].chars()
+                    .find(|&c| g[1].contains(c) && g[2].contains(c))
+                    .unwrap(),
+            )
+        })
+        .sum::<usize>();
+    [part1.into(), part2.into()]
+}
+
+fn section_pairs(text: &str) -> [Answer; 2] {
+    let pairs = text
+        .lines()
+        .map(|l| {
+            let n = numbers(&l.replace('-', " "));
+            let first = (n[0]..=n[1]).collect::<HashSet<i64>>();
+            let second = (n[2]..=n[3]).collect::<HashSet<i64>>();
+            (first, second)
+        })
+        .collect::<Vec<_>>();
+    let contained = pairs
+        .iter()
+        .filter(|(a, b)| a.is_subset(b) || b.is_subset(a))
+        .count();
+    let overlapping = pairs.iter().filter(|(a, b)| !a.is_disjoint(b)).count();
+    [contained.into(), overlapping.into()]
+}
+
+fn crate_stacks(text: &str) -> [Answer; 2] {
+    let (drawing, moves) = text.split_once("\n\n").unwrap();
+    let mut rows = drawing.lines().collect::<Vec<&str>>();
+    let count = rows.pop().unwrap().split_whitespace().count();
+    let mut stacks = vec![vec![]; count];
+    for row in rows.iter().rev() {
+        for (i, stack) in stacks.iter_mut().enumerate() {
+            if let Some(c) = row.chars().nth(i * 4 + 1).filter(char::is_ascii_uppercase) {
+                stack.push(c);
+            }
+        }
+    }
+    let moves = moves.lines().map(numbers).collect::<Vec<Vec<i64>>>();
+    let rearrange = |one_at_a_time: bool| {
+        let mut stacks = stacks.clone();
+        for m in &moves {
+            let (count, src, dst) = (m[0] as usize, m[1] as usize - 1, m[2] as usize - 1);
+            let at = stacks[src].len() - count;
+            let mut moved = stacks[src].split_off(at);
+            if one_at_a_time {
+                moved.reverse();
+            }
+            stacks[dst].extend(moved);
+        }
+        stacks.iter().map(|s| s[s.len() - 1]).collect::<String>()
+    };
+    [rearrange(true).into(), rearrange(false).into()]
+}
+
+fn datastream(text: &str, window1: i64, window2: i64) -> [Answer; 2] {
+    let chars = text.trim_end().chars().collect::<Vec<char>>();
+    let marker = |window: i64| {
+        let window = window as usize;
+        (window..=chars.len())
+            .find(|&end| {
+                let w = &chars[end - window..end];
+                w.iter().enumerate().all(|(i, c)| !w[..i].contains(c))
+            })
+            .unwrap()
+    };
+    [marker(window1).into(), marker(window2).into()]
+}
+
+fn terminal_output(text: &str, small_dir: i64, disk_size: i64, required_space: i64) -> [Answer; 2] {
+    // Sizes of the directories on the current path, and of those left
+    let mut path = vec![0];
+    let mut sizes = vec![];
+    let mut leave = |path: &mut Vec<i64>| {
+        let size = path.pop().unwrap();
+        if let Some(parent) = path.last_mut() {
+            *parent += size;
+        }
+        sizes.push(size);
+    };
+    for line in text.lines() {
+        match line.split(' ').collect::<Vec<&str>>()[..] {
+            ["$", "cd", "/"] => {
+                while path.len() > 1 {
+                    leave(&mut path);
+                }
+            }
+            ["$", "cd", ".."] => leave(&mut path),
+            ["$", "cd", _] => path.push(0),
+            ["$", "ls"] | ["dir", _] => {}
+            [size, _] => *path.last_mut().unwrap() += size.parse::<i64>().unwrap(),
+            _ => panic!("Unexpected line {line}"),
+        }
+    }
+    while !path.is_empty() {
+        leave(&mut path);
+    }
+    let used = sizes[sizes.len() - 1];
+    let to_delete = required_space - (disk_size - used);
+    let part1 = sizes.iter().filter(|&&s| s <= small_dir).sum::<i64>();
+    let part2 = sizes.iter().filter(|&&s| s >= to_delete).min().unwrap();
+    [part1.into(), (*part2).into()]
+}
+
+fn tree_heights(text: &str) -> [Answer; 2] {
+    let grid = text.lines().map(str::as_bytes).collect::<Vec<&[u8]>>();
+    let (rows, columns) = (grid.len() as i64, grid[0].len() as i64);
+    let mut visible = 0;
+    let mut best_score = 0;
+    for r in 0..rows {
+        for c in 0..columns {
+            let height = grid[r as usize][c as usize];
+            let mut is_visible = false;
+            let mut score = 1;
+            for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (mut tr, mut tc) = (r + dr, c + dc);
+                let mut distance = 0;
+                let mut blocked = false;
+                while (0..rows).contains(&tr) && (0..columns).contains(&tc) {
+                    distance += 1;
+                    if grid[tr as usize][tc as usize] >= height {
+                        blocked = true;
+                        break;
+                    }
+                    tr += dr;
+                    tc += dc;
+                }
+                is_visible |= !blocked;
+                score *= distance;
+            }
+            visible += usize::from(is_visible);
+            best_score = best_score.max(score);
+        }
+    }
+    [visible.into(), best_score.into()]
+}
+
+fn rope_motions(text: &str, knots1: i64, knots2: i64) -> [Answer; 2] {
+    let visited = |knots: i64| {
+        let mut rope = vec![(0_i64, 0_i64); knots as usize];
+        let mut visited = HashSet::from([(0, 0)]);
+        for line in text.lines() {
+            let (direction, steps) = line.split_once(' ').unwrap();
+            let (dx, dy) = match direction {
+                "U" => (0, 1),
+                "D" => (0, -1),
+                "L" => (-1, 0),
+                _ => (1, 0),
+            };
+            for _ in 0..steps.parse::<u32>().unwrap() {
+                rope[0] = (rope[0].0 + dx, rope[0].1 + dy);
+                for k in 1..rope.len() {
+                    let (x, y) = (rope[k - 1].0 - rope[k].0, rope[k - 1].1 - rope[k].1);
+                    if x.abs() > 1 || y.abs() > 1 {
+                        rope[k] = (rope[k].0 + x.signum(), rope[k].1 + y.signum());
+                    }
+                }
+                visited.insert(rope[rope.len() - 1]);
+            }
+        }
+        visited.len()
+    };
+    [visited(knots1).into(), visited(knots2).into()]
+}
+
+fn cpu_program(text: &str) -> [Answer; 2] {
+    // The value of X during each cycle
+    let mut xs = vec![];
+    let mut x = 1_i64;
+    for line in text.lines() {
+        xs.push(x);
+        if let Some(value) = line.strip_prefix("addx ") {
+            xs.push(x);
+            x += value.parse::<i64>().unwrap();
+        }
+    }
+    let part1 = [20, 60, 100, 140, 180, 220]
+        .iter()
+        .map(|&cycle| cycle as i64 * xs[cycle - 1])
+        .sum::<i64>();
+    let screen = (0..6).map(|row| {
+        (0..40)
+            .map(|column| {
+                let lit = (xs[row * 40 + column] - column as i64).abs() <= 1;
+                if lit {
+                    '#'
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>()
+    });
+    [part1.into(), Answer::grid(screen)]
+}
+
+fn monkey_notes(text: &str, rounds1: i64, rounds2: i64) -> [Answer; 2] {
+    let monkeys = text
+        .split("\n\n")
+        .map(|notes| {
+            let lines = notes.lines().collect::<Vec<&str>>();
+            let last_word = |i: usize| lines[i].split(' ').next_back().unwrap();
+            let items = numbers(lines[1].split_once(':').unwrap().1);
+            let operation = lines[2].split(' ').rev().take(2).collect::<Vec<&str>>();
+            let operand = operation[0].parse::<i64>().ok();
+            let multiply = operation[1] == "*";
+            let divisor = last_word(3).parse::<i64>().unwrap();
+            let targets = [4, 5].map(|i| last_word(i).parse::<usize>().unwrap());
+            (items, multiply, operand, divisor, targets)
+        })
+        .collect::<Vec<_>>();
+    let apply = |multiply: bool, operand: Option<i64>, old: i128| {
+        let operand = operand.map_or(old, i128::from);
+        if multiply {
+            old * operand
+        } else {
+            old + operand
+        }
+    };
+    let business = |mut inspections: Vec<i64>| {
+        inspections.sort_unstable_by(|a, b| b.cmp(a));
+        inspections[0] * inspections[1]
+    };
+
+    // Part 1 plays with the worry levels themselves
+    let mut items = monkeys
+        .iter()
+        .map(|m| {
+            m.0.iter()
+                .map(|&i| i128::from(i))
+                .collect::<VecDeque<i128>>()
+        })
+        .collect::<Vec<_>>();
+    let mut inspections = vec![0; monkeys.len()];
+    for _ in 0..rounds1 {
+        for (i, &(_, multiply, operand, divisor, targets)) in monkeys.iter().enumerate() {
+            while let Some(old) = items[i].pop_front() {
+                inspections[i] += 1;
+                let new = apply(multiply, operand, old) / 3;
+                let target = targets[usize::from(new % i128::from(divisor) != 0)];
+                items[target].push_back(new);
+            }
+        }
+    }
+    let part1 = business(inspections);
+
+    // Part 2 keeps each item as its remainder by every monkey's divisor
+    let divisors = monkeys.iter().map(|m| i128::from(m.3)).collect::<Vec<_>>();
+    let mut items = monkeys
+        .iter()
+        .map(|m| {
+            m.0.iter()
+                .map(|&i| divisors.iter().map(|d| i128::from(i) % d).collect())
+                .collect::<VecDeque<Vec<i128>>>()
+        })
+        .collect::<Vec<_>>();
+    let mut inspections = vec![0; monkeys.len()];
+    for _ in 0..rounds2 {
+        for (i, &(_, multiply, operand, _, targets)) in monkeys.iter().enumerate() {
+            while let Some(remainders) = items[i].pop_front() {
+                inspections[i] += 1;
+                let new = remainders
+                    .iter()
+                    .zip(&divisors)
+                    .map(|(&r, d)| apply(multiply, operand, r) % d)
+                    .collect::<Vec<i128>>();
+                let target = targets[usize::from(new[i] != 0)];
+                items[target].push_back(new);
+            }
+        }
+    }
+    [part1.into(), business(inspections).into()]
+}
+
+fn heightmap(text: &str) -> [Answer; 2] {
+    let grid = text.lines().map(str::as_bytes).collect::<Vec<&[u8]>>();
+    let height = |c: u8| match c {
+        b'S' => b'a',
+        b'E' => b'z',
+        c => c,
+    };
+    let cells = (0..grid.len()).flat_map(|r| (0..grid[r].len()).map(move |c| (r, c)));
+    // Climb from every start at once, one step at a time
+    let steps = |starts: Vec<(usize, usize)>| {
+        let mut seen = starts.iter().copied().collect::<HashSet<_>>();
+        let mut frontier = starts;
+        let mut steps = 0;
+        while !frontier.is_empty() {
+            let mut next = vec![];
+            for (r, c) in frontier {
+                if grid[r][c] == b'E' {
+                    return steps;
+                }
+                let neighbours = [
+                    (r + 1, c),
+                    (r, c + 1),
+                    (r.wrapping_sub(1), c),
+                    (r, c.wrapping_sub(1)),
+                ];
+                for (nr, nc) in neighbours {
+                    let Some(&n) = grid.get(nr).and_then(|row| row.get(nc)) else {
+                        continue;
+                    };
+                    if height(n) <= height(grid[r][c]) + 1 && seen.insert((nr, nc)) {
+                        next.push((nr, nc));
+                    }
+                }
+            }
+            frontier = next;
+            steps += 1;
+        }
+        panic!("The goal cannot be reached");
+    };
+    let start = cells.clone().filter(|&(r, c)| grid[r][c] == b'S').collect();
+    let lowest = cells.filter(|&(r, c)| height(grid[r][c]) == b'a').collect();
+    [steps(start).into(), steps(lowest).into()]
+}
+
+fn compare_packets(left: &Value, right: &Value) -> Ordering {
+    match (left, right) {
+        (Value::Array(l), Value::Array(r)) => l
+            .iter()
+            .zip(r)
+            .map(|(a, b)| compare_packets(a, b))
+            .find(|o| o.is_ne())
+            .unwrap_or(l.len().cmp(&r.len())),
+        (Value::Array(_), n) => compare_packets(left, &Value::Array(vec![n.clone()])),
+        (n, Value::Array(_)) => compare_packets(&Value::Array(vec![n.clone()]), right),
+        (l, r) => l.as_u64().cmp(&r.as_u64()),
+    }
+}
+
+fn packet_pairs(text: &str) -> [Answer; 2] {
+    let packets = text
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| serde_json::from_str::<Value>(l).unwrap())
+        .collect::<Vec<Value>>();
+    let part1 = packets
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| compare_packets(&pair[0], &pair[1]).is_lt())
+        .map(|(i, _)| i + 1)
+        .sum::<usize>();
+    // Each divider's index is one more than the number of packets before it
+    let before = |divider: &str| {
+        let divider = serde_json::from_str::<Value>(divider).unwrap();
+        packets
+            .iter()
+            .filter(|p| compare_packets(p, &divider).is_lt())
+            .count()
+    };
+    let part2 = (before("[[2]]") + 1) * (before("[[6]]") + 2);
+    [part1.into(), part2.into()]
+}
+
+fn rock_paths(text: &str) -> [Answer; 2] {
+    let mut rock = HashSet::new();
+    for line in text.lines() {
+        let points = line
+            .split(" -> ")
+            .map(|p| {
+                let n = numbers(p);
+                (n[0], n[1])
+            })
+            .collect::<Vec<(i64, i64)>>();
+        for w in points.windows(2) {
+            for x in w[0].0.min(w[1].0)..=w[0].0.max(w[1].0) {
+                for y in w[0].1.min(w[1].1)..=w[0].1.max(w[1].1) {
+                    rock.insert((x, y));
+                }
+            }
+        }
+    }
+    let lowest = rock.iter().map(|&(_, y)| y).max().unwrap();
+
+    // Drop each grain from the top until one falls past the lowest rock or
+    // the sand piles up to the source
+    let mut cave = rock.clone();
+    let mut part1 = 0;
+    'grains: loop {
+        let (mut x, mut y) = (500, 0);
+        loop {
+            if y > lowest {
+                break 'grains;
+            }
+            match [x, x - 1, x + 1]
+                .into_iter()
+                .find(|&nx| !cave.contains(&(nx, y + 1)))
+            {
+                Some(nx) => (x, y) = (nx, y + 1),
+                None => break,
+            }
+        }
+        cave.insert((x, y));
+        part1 += 1;
+        if (x, y) == (500, 0) {
+            break;
+        }
+    }
+
+    // With a floor, sand fills every cell it can reach from the source
+    let floor = lowest + 2;
+    let mut row = HashSet::from([500_i64]);
+    let mut part2 = 0;
+    for y in 0..floor {
+        part2 += row.len();
+        row = row
+            .iter()
+            .flat_map(|&x| [x - 1, x, x + 1])
+            .filter(|&x| y + 1 < floor && !rock.contains(&(x, y + 1)))
+            .collect();
+    }
+    [part1.into(), part2.into()]
+}
+
+fn sensor_report(text: &str, row: i64, limit: i64) -> [Answer; 2] {
+    let sensors = text
+        .lines()
+        .map(|l| {
+            let n = numbers(l);
+            (
+                (n[0], n[1]),
+                (n[2], n[3]),
+                (n[0] - n[2]).abs() + (n[1] - n[3]).abs(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let covered = |x: i64, y: i64| {
+        sensors
+            .iter()
+            .any(|&((sx, sy), _, r)| (sx - x).abs() + (sy - y).abs() <= r)
+    };
+    let min_x = sensors.iter().map(|&((sx, _), _, r)| sx - r).min().unwrap();
+    let max_x = sensors.iter().map(|&((sx, _), _, r)| sx + r).max().unwrap();
+    let part1 = (min_x..=max_x)
+        .filter(|&x| covered(x, row) && !sensors.iter().any(|&(_, b, _)| b == (x, row)))
+        .count();
+    let uncovered = (0..=limit)
+        .flat_map(|x| (0..=limit).map(move |y| (x, y)))
+        .filter(|&(x, y)| !covered(x, y))
+        .collect::<Vec<(i64, i64)>>();
+    let [(x, y)] = uncovered[..] else {
+        panic!("Expected one uncovered position, found {}", uncovered.len());
+    };
+    [part1.into(), (x * 4_000_000 + y).into()]
+}
+
+/// One part of a solution checked against the reference on a generated input
+pub struct Comparison {
+    pub knobs: Knobs,
+    pub expected: Answer,
+    pub result: PartResult,
+}
+
+impl Comparison {
+    pub fn agrees(&self) -> bool {
+        matches!(&self.result.outcome, Outcome::Answer(answer) if answer == &self.expected)
+    }
+}
+
+/// Generate an input for `day` and solve both parts of it with the
+/// registered solution and the reference. `None` if the day is not
+/// registered or has no generator.
+pub fn compare(registry: &Registry, day: usize, knobs: Knobs) -> Option<Vec<Comparison>> {
+    let input = generate(day, knobs)?;
+    let expected = solve(day, &input)?;
+    let solver = registry.solver_with(day, Source::Text(input.text), &input.params)?;
+    let comparisons = expected
+        .into_iter()
+        .zip(1..)
+        .map(|(expected, part)| Comparison {
+            knobs,
+            expected,
+            result: solve_part(solver.as_ref(), part),
+        })
+        .collect();
+    Some(comparisons)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::manifest::Manifest;
+    use crate::params::{config_path, read_config};
+    use crate::solvable::data_dir;
+
+    fn assert_agrees(day: usize, knobs: Knobs) {
+        let comparisons = compare(&Registry::default(), day, knobs)
+            .unwrap_or_else(|| panic!("Day {day} has no generator"));
+        for c in comparisons {
+            let got = match &c.result.outcome {
+                Outcome::Answer(answer) => answer.to_string(),
+                Outcome::Error(e) => e.to_string(),
+                Outcome::Panic(message) => format!("panic: {message}"),
+            };
+            assert!(
+                c.agrees(),
+                "Day {day}, part {}, {knobs:?}: expected {}, got {got}",
+                c.result.part,
+                c.expected
+            );
+        }
+    }
+
+    #[test]
+    fn it_agrees_with_every_solution_on_small_inputs() {
+        // Days without a generator yet, like a newly scaffolded one, are skipped
+        let registry = Registry::default();
+        for day in registry
+            .days()
+            .filter(|&d| generate(d, Knobs { seed: 0, size: 12 }).is_some())
+        {
+            for seed in 0..5 {
+                assert_agrees(day, Knobs { seed, size: 12 });
+            }
+        }
+    }
+
+    #[test]
+    fn it_agrees_on_the_example_inputs() {
+        // Every example with expected answers in its sidecar
+        let mut checked = vec![];
+        for day in 1..=25 {
+            let Ok(manifest) = Manifest::load(day) else {
+                continue;
+            };
+            let path = data_dir(day).join("test1.txt");
+            let (Some(part1), Some(part2)) = (
+                manifest.expected("test1.txt", 1),
+                manifest.expected("test1.txt", 2),
+            ) else {
+                continue;
+            };
+            let text = std::fs::read_to_string(&path).unwrap();
+            let params = read_config(day, &config_path(&path)).unwrap();
+            let generated = Generated {
+                text,
+                params: params.into_iter().collect(),
+            };
+            let Some(answers) = solve(day, &generated) else {
+                continue;
+            };
+            assert_eq!(answers, [part1.clone(), part2.clone()], "Day {day}");
+            checked.push(day);
+        }
+        assert!(checked.len() >= 15, "Only checked days {checked:?}");
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn it_agrees_on_deep_directory_trees() {
+        assert_agrees(
+            7,
+            Knobs {
+                seed: 3,
+                size: 5000,
+            },
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day14")]
+    fn it_agrees_when_sand_spreads_past_x_0() {
+        assert_agrees(14, Knobs { seed: 5, size: 520 });
+    }
+}
@@ -0,0 +1,277 @@
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::{Override, Param, Params};
+use crate::solvable::{Simulation, Solvable, Source};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
+
+/// Object-safe view of a `Solvable<N>`, so that days can be stored and
+/// iterated over at runtime. The input is parsed on first use and shared by
+/// both parts.
+pub trait Solver {
+    fn day(&self) -> usize;
+    /// Parameters the puzzle declares, with their defaults
+    fn declared_params(&self) -> &'static [Param];
+    /// The parameters this input is solved with
+    fn params(&self) -> Result<&Params, AocError>;
+    /// Read and parse the input, unless that has already happened
+    fn parse(&self) -> Result<(), AocError>;
+    fn answer1(&self) -> Result<Answer, AocError>;
+    fn answer2(&self) -> Result<Answer, AocError>;
+    /// Solving `part` a step at a time, if the day supports it
+    fn simulation(&self, part: u8) -> Result<Option<Box<dyn Simulation + '_>>, AocError>;
+
+    fn answer(&self, part: u8) -> Result<Answer, AocError> {
+        match part {
+            1 => self.answer1(),
+            2 => self.answer2(),
+            _ => Err(AocError::unsolvable(format!("There is no part {part}")).with_day(self.day())),
+        }
+    }
+}
+
+struct Day<const N: usize, S: Solvable<N>> {
+    solution: S,
+    overrides: Vec<Override>,
+    params: OnceCell<Params>,
+    input: OnceCell<S::Input>,
+}
+
+impl<const N: usize, S: Solvable<N>> Day<N, S> {
+    fn input(&self) -> Result<&S::Input, AocError> {
+        if let Some(input) = self.input.get() {
+            return Ok(input);
+        }
+        let input = self.solution.input()?;
+        Ok(self.input.get_or_init(|| input))
+    }
+
+    fn solve<F>(&self, part: F) -> Result<Answer, AocError>
+    where
+        F: FnOnce(&S, &S::Input, &Params) -> Result<Answer, AocError>,
+    {
+        part(&self.solution, self.input()?, self.params()?)
+            .map_err(|e| self.solution.source().context(N, e))
+    }
+}
+
+impl<const N: usize, S: Solvable<N>> Solver for Day<N, S> {
+    fn day(&self) -> usize {
+        N
+    }
+
+    fn declared_params(&self) -> &'static [Param] {
+        S::PARAMS
+    }
+
+    fn params(&self) -> Result<&Params, AocError> {
+        if let Some(params) = self.params.get() {
+            return Ok(params);
+        }
+        let params = Params::resolve(N, S::PARAMS, self.solution.source(), &self.overrides)
+            .map_err(|e| e.with_day(N))?;
+        Ok(self.params.get_or_init(|| params))
+    }
+
+    fn parse(&self) -> Result<(), AocError> {
+        self.input().map(|_| ())
+    }
+
+    fn answer1(&self) -> Result<Answer, AocError> {
+        self.solve(S::part1)
+    }
+
+    fn answer2(&self) -> Result<Answer, AocError> {
+        self.solve(S::part2)
+    }
+
+    fn simulation(&self, part: u8) -> Result<Option<Box<dyn Simulation + '_>>, AocError> {
+        self.solution
+            .simulation(self.input()?, self.params()?, part)
+            .map_err(|e| self.solution.source().context(N, e))
+    }
+}
+
+pub type Constructor = fn(Source, &[Override]) -> Box<dyn Solver>;
+
+pub struct Registry {
+    days: BTreeMap<usize, Constructor>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            days: BTreeMap::new(),
+        }
+    }
+
+    pub fn register<const N: usize, S: Solvable<N> + 'static>(&mut self) {
+        self.days.insert(N, |input, overrides| {
+            Box::new(Day::<N, S> {
+                solution: S::from_source(input),
+                overrides: overrides.to_vec(),
+                params: OnceCell::new(),
+                input: OnceCell::new(),
+            })
+        });
+    }
+
+    pub fn days(&self) -> impl Iterator<Item = usize> + '_ {
+        self.days.keys().copied()
+    }
+
+    pub fn contains(&self, day: usize) -> bool {
+        self.days.contains_key(&day)
+    }
+
+    pub fn solver(&self, day: usize, filename: &str) -> Option<Box<dyn Solver>> {
+        self.solver_from(day, Source::file(day, filename))
+    }
+
+    pub fn solver_from(&self, day: usize, input: Source) -> Option<Box<dyn Solver>> {
+        self.solver_with(day, input, &[])
+    }
+
+    /// A solver for `input` with parameters set by `overrides`, on top of any
+    /// in the input's config file
+    pub fn solver_with(
+        &self,
+        day: usize,
+        input: Source,
+        overrides: &[Override],
+    ) -> Option<Box<dyn Solver>> {
+        self.days
+            .get(&day)
+            .map(|constructor| constructor(input, overrides))
+    }
+
+    pub fn solvers<'a>(&'a self, filename: &'a str) -> impl Iterator<Item = Box<dyn Solver>> + 'a {
+        self.days
+            .iter()
+            .map(move |(&day, constructor)| constructor(Source::file(day, filename), &[]))
+    }
+}
+
+impl Default for Registry {
+    /// A registry containing every day enabled by its cargo feature
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Registry::new();
+        #[cfg(feature = "day1")]
+        registry.register::<1, crate::day1::Solution>();
+        #[cfg(feature = "day2")]
+        registry.register::<2, crate::day2::Solution>();
+        #[cfg(feature = "day3")]
+        registry.register::<3, crate::day3::Solution>();
+        #[cfg(feature = "day4")]
+        registry.register::<4, crate::day4::Solution>();
+        #[cfg(feature = "day5")]
+        registry.register::<5, crate::day5::Solution>();
+        #[cfg(feature = "day6")]
+        registry.register::<6, crate::day6::Solution>();
+        #[cfg(feature = "day7")]
+        registry.register::<7, crate::day7::Solution>();
+        #[cfg(feature = "day8")]
+        registry.register::<8, crate::day8::Solution>();
+        #[cfg(feature = "day9")]
+        registry.register::<9, crate::day9::Solution>();
+        #[cfg(feature = "day10")]
+        registry.register::<10, crate::day10::Solution>();
+        #[cfg(feature = "day11")]
+        registry.register::<11, crate::day11::Solution>();
+        #[cfg(feature = "day12")]
+        registry.register::<12, crate::day12::Solution>();
+        #[cfg(feature = "day13")]
+        registry.register::<13, crate::day13::Solution>();
+        #[cfg(feature = "day14")]
+        registry.register::<14, crate::day14::Solution>();
+        #[cfg(feature = "day15")]
+        registry.register::<15, crate::day15::Solution>();
+        registry
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+mod test {
+    use super::*;
+    use std::error::Error;
+
+    #[test]
+    #[cfg(feature = "all")]
+    fn it_registers_every_day() {
+        let mut declared = include_str!("lib.rs")
+            .lines()
+            .filter_map(|l| {
+                l.strip_prefix("pub mod day")?
+                    .strip_suffix(';')?
+                    .parse()
+                    .ok()
+            })
+            .collect::<Vec<usize>>();
+        declared.sort();
+        assert_eq!(Registry::default().days().collect::<Vec<usize>>(), declared);
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn it_builds_a_solver_for_the_requested_day() -> Result<(), Box<dyn Error>> {
+        let registry = Registry::default();
+        let solver = registry.solver(7, "test1.txt").ok_or("Day 7 missing")?;
+        assert_eq!(solver.day(), 7);
+        assert_eq!(solver.answer(1)?, "95437");
+        assert_eq!(solver.answer(2)?, "24933642");
+        assert!(solver.answer(3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn it_builds_a_solver_for_inline_text() -> Result<(), Box<dyn Error>> {
+        let input = Source::Text("mjqjpqmgbljsphdztnvjfqwrcgsmlb\n".to_string());
+        let solver = Registry::default()
+            .solver_from(6, input)
+            .ok_or("Day 6 missing")?;
+        assert_eq!(solver.answer(1)?, 7);
+        assert_eq!(solver.answer(2)?, 19);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn it_parses_the_input_once() -> Result<(), Box<dyn Error>> {
+        let path = std::env::temp_dir().join(format!("aoc-parse-once-{}.txt", std::process::id()));
+        std::fs::write(&path, "100\n\n200\n")?;
+        let solver = Registry::default()
+            .solver_from(1, Source::File(path.clone()))
+            .ok_or("Day 1 missing")?;
+        solver.parse()?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(solver.answer(1)?, 200);
+        assert_eq!(solver.answer(2)?, 300);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn it_overrides_parameters() -> Result<(), Box<dyn Error>> {
+        let overrides = [("window1".to_string(), 14)];
+        let solver = Registry::default()
+            .solver_with(6, Source::file(6, "test1.txt"), &overrides)
+            .ok_or("Day 6 missing")?;
+        assert_eq!(solver.answer(1)?, solver.answer(2)?);
+
+        let unknown = [("row".to_string(), 10)];
+        let solver = Registry::default()
+            .solver_with(6, Source::file(6, "test1.txt"), &unknown)
+            .ok_or("Day 6 missing")?;
+        assert!(solver.answer(1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_has_no_solver_for_unregistered_days() {
+        assert!(Registry::new().solver(1, "test1.txt").is_none());
+        assert!(!Registry::default().contains(26));
+    }
+}
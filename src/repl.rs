@@ -0,0 +1,146 @@
+use crate::registry::Solver;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+show        Render the current state
+step [N]    Advance the simulation N steps, 1 by default
+run         Advance the simulation until it finishes
+reset       Start the simulation again
+part P      Simulate part P instead, from the start
+answer P    Solve part P of the puzzle
+quit        Leave";
+
+/// Read commands from `input` and write their results to `output`, until
+/// `quit` or the end of the input
+pub fn run(
+    solver: &dyn Solver,
+    part: u8,
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> io::Result<()> {
+    let mut part = part;
+    let mut simulation = solver.simulation(part);
+    let mut steps = 0;
+    loop {
+        write!(output, "day{} part{part}> ", solver.day())?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return writeln!(output);
+        }
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let number = |default: usize| match words.get(1) {
+            Some(word) => word.parse::<usize>().map_err(|e| format!("{word}: {e}")),
+            None => Ok(default),
+        };
+
+        let result = match (words.first().copied(), &mut simulation) {
+            (None, _) => Ok(String::new()),
+            (Some("help"), _) => Ok(HELP.to_string()),
+            (Some("quit" | "exit"), _) => return Ok(()),
+            (Some("answer"), _) => match number(part as usize) {
+                Ok(p @ 1..=2) => solver
+                    .answer(p as u8)
+                    .map(|a| a.to_string())
+                    .map_err(|e| e.to_string()),
+                Ok(p) => Err(format!("There is no part {p}")),
+                Err(e) => Err(e),
+            },
+            (Some("part"), _) => match number(0) {
+                Ok(p @ 1..=2) => {
+                    part = p as u8;
+                    simulation = solver.simulation(part);
+                    steps = 0;
+                    Ok(format!("Simulating part {part} from the start"))
+                }
+                Ok(p) => Err(format!("There is no part {p}")),
+                Err(e) => Err(e),
+            },
+            (Some("reset"), _) => {
+                simulation = solver.simulation(part);
+                steps = 0;
+                Ok(format!("Simulating part {part} from the start"))
+            }
+            (Some("show" | "step" | "run"), Err(e)) => Err(e.to_string()),
+            (Some("show" | "step" | "run"), Ok(None)) => Err(format!(
+                "Day {} has no simulation; try `answer {part}`",
+                solver.day()
+            )),
+            (Some("show"), Ok(Some(simulation))) => Ok(simulation.to_string()),
+            (Some(command @ ("step" | "run")), Ok(Some(simulation))) => {
+                let limit = match command {
+                    "run" => Ok(usize::MAX),
+                    _ => number(1),
+                };
+                limit.and_then(|limit| {
+                    let mut finished = false;
+                    for _ in 0..limit {
+                        if !simulation.step().map_err(|e| e.to_string())? {
+                            finished = true;
+                            break;
+                        }
+                        steps += 1;
+                    }
+                    Ok(match finished {
+                        true => format!("Finished after {steps} steps"),
+                        false => format!("Step {steps}"),
+                    })
+                })
+            }
+            (Some(command), _) => Err(format!("Unknown command {command}; try `help`")),
+        };
+        match result {
+            Ok(text) if text.is_empty() => {}
+            Ok(text) => writeln!(output, "{text}")?,
+            Err(e) => writeln!(output, "error: {e}")?,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+mod test {
+    use super::*;
+    use crate::registry::Registry;
+    use crate::solvable::Source;
+
+    #[cfg(any(feature = "day1", feature = "day5"))]
+    fn session(day: usize, text: &str, commands: &str) -> String {
+        let solver = Registry::default()
+            .solver_from(day, Source::Text(text.to_string()))
+            .unwrap();
+        let mut output = vec![];
+        run(solver.as_ref(), 1, commands.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "day5")]
+    fn it_steps_through_a_simulation() {
+        let stacks = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\n\
+                      move 1 from 2 to 1\nmove 2 from 1 to 3\n";
+        let output = session(
+            5,
+            stacks,
+            "step\nshow\nrun\nstep\npart 2\nanswer\nquit\nshow\n",
+        );
+        let replies = output.split("day5 ").collect::<Vec<&str>>();
+        assert_eq!(replies[1], "part1> Step 1\n");
+        assert!(replies[2].starts_with("part1> [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3\n"));
+        assert!(replies[2].ends_with("Next, 2 of 2: move 2 from 1 to 3\n"));
+        assert_eq!(replies[3], "part1> Finished after 2 steps\n");
+        assert_eq!(replies[4], "part1> Finished after 2 steps\n");
+        assert_eq!(replies[5], "part1> Simulating part 2 from the start\n");
+        assert_eq!(replies[6], "part2> ZCD\n");
+        assert_eq!(replies.len(), 8, "{output}");
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn it_reports_days_without_a_simulation() {
+        let output = session(1, "1\n2\n", "step\nanswer 3\nanswer 1\n");
+        assert!(output.contains("error: Day 1 has no simulation"));
+        assert!(output.contains("error: There is no part 3"));
+        assert!(output.ends_with("day1 part1> 3\nday1 part1> \n"));
+    }
+}
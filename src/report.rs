@@ -0,0 +1,134 @@
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::registry::Solver;
+use serde::Serialize;
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::{Duration, Instant};
+
+pub enum Outcome {
+    Answer(Answer),
+    Error(AocError),
+    /// The solution panicked; holds the panic message
+    Panic(String),
+}
+
+pub struct PartResult {
+    pub day: usize,
+    pub part: u8,
+    pub outcome: Outcome,
+    /// Time spent solving, not counting reading and parsing the input
+    pub elapsed: Duration,
+}
+
+impl PartResult {
+    pub fn succeeded(&self) -> bool {
+        matches!(self.outcome, Outcome::Answer(_))
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "Panicked with a non-string payload".to_string(),
+        },
+    }
+}
+
+/// Parse the input of `solver` if needed, then solve and time `part`. A
+/// panic is caught and reported as the outcome.
+pub fn solve_part(solver: &dyn Solver, part: u8) -> PartResult {
+    let mut elapsed = Duration::ZERO;
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        solver.parse()?;
+        let start = Instant::now();
+        let answer = solver.answer(part);
+        elapsed = start.elapsed();
+        answer
+    }));
+    let outcome = match result {
+        Ok(Ok(answer)) => Outcome::Answer(answer),
+        Ok(Err(e)) => Outcome::Error(e),
+        Err(payload) => Outcome::Panic(panic_message(payload)),
+    };
+    PartResult {
+        day: solver.day(),
+        part,
+        outcome,
+        elapsed,
+    }
+}
+
+/// A part's result as written by `--format json`:
+///
+/// ```json
+/// {"day":10,"part":1,"input":"input.txt","answer":{"type":"integer","value":15360},
+///  "duration_ns":15976,"error":null}
+/// ```
+#[derive(Serialize)]
+pub struct Record<'a> {
+    pub day: usize,
+    pub part: u8,
+    pub input: &'a str,
+    pub answer: Option<&'a Answer>,
+    pub duration_ns: u64,
+    pub error: Option<String>,
+}
+
+impl<'a> Record<'a> {
+    pub fn new(input: &'a str, result: &'a PartResult) -> Self {
+        let (answer, error) = match &result.outcome {
+            Outcome::Answer(answer) => (Some(answer), None),
+            Outcome::Error(e) => (None, Some(e.to_string())),
+            Outcome::Panic(message) => (None, Some(format!("panicked: {message}"))),
+        };
+        Record {
+            day: result.day,
+            part: result.part,
+            input,
+            answer,
+            duration_ns: result.elapsed.as_nanos() as u64,
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+mod test {
+    use super::*;
+    use crate::registry::Registry;
+
+    #[test]
+    #[cfg(feature = "day10")]
+    fn it_records_answers_with_their_type() -> Result<(), Box<dyn std::error::Error>> {
+        let solver = Registry::default()
+            .solver(10, "test1.txt")
+            .ok_or("Day 10 missing")?;
+        let result = solve_part(solver.as_ref(), 2);
+        let json = serde_json::to_value(Record::new("test1.txt", &result))?;
+        assert_eq!(json["day"], 10);
+        assert_eq!(json["input"], "test1.txt");
+        assert_eq!(json["answer"]["type"], "grid");
+        assert_eq!(json["answer"]["value"].as_array().map(Vec::len), Some(6));
+        assert!(json["error"].is_null());
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn it_records_errors_without_an_answer() -> Result<(), Box<dyn std::error::Error>> {
+        let solver = Registry::default()
+            .solver(1, "missing.txt")
+            .ok_or("Day 1 missing")?;
+        let result = solve_part(solver.as_ref(), 1);
+        let json = serde_json::to_value(Record::new("missing.txt", &result))?;
+        assert!(json["answer"].is_null());
+        assert!(json["error"]
+            .as_str()
+            .is_some_and(|e| e.contains("missing.txt")));
+        Ok(())
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs;
+use std::io::{Error, ErrorKind, Result};
+use std::path::{Path, PathBuf};
+
+/// Root of the source tree this binary was built from
+pub fn project_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Render `src/template.txt` for `day`
+pub fn render_template(template: &str, day: usize) -> String {
+    template.replace("{{day}}", &day.to_string())
+}
+
+/// The attribute compiling `day` only when its feature is enabled
+fn feature_gate(day: usize) -> String {
+    format!("#[cfg(feature = \"day{day}\")]")
+}
+
+/// Insert `pub mod dayN;` and its feature gate into the block of day
+/// modules, keeping the block in the same order rustfmt would.
+fn add_module(lib: &str, day: usize) -> Result<String> {
+    let declaration = format!("pub mod day{day};");
+    let mut lines = lib.lines().map(String::from).collect::<Vec<String>>();
+    if lines.contains(&declaration) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("lib.rs already declares day{day}"),
+        ));
+    }
+    let is_day_module =
+        |l: &String| l.starts_with("pub mod day") || l.starts_with("#[cfg(feature = \"day");
+    let start = lines.iter().position(is_day_module).unwrap_or(0);
+    let end = start
+        + lines[start..]
+            .iter()
+            .take_while(|l| is_day_module(l))
+            .count();
+    // Each declaration, with the attributes above it
+    let mut modules = vec![vec![feature_gate(day), declaration]];
+    let mut attributes = vec![];
+    for line in lines.drain(start..end) {
+        let is_declaration = line.starts_with("pub mod");
+        attributes.push(line);
+        if is_declaration {
+            modules.push(std::mem::take(&mut attributes));
+        }
+    }
+    modules.sort_by_key(|m| m.last().map(|l| l.trim_end_matches(';').to_string()));
+    lines.splice(start..start, modules.concat());
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Add a registration for `day` to the default registry, in day order
+fn add_registration(registry: &str, day: usize) -> Result<String> {
+    const MARKER: &str = "registry.register::<";
+    let registration_day = |l: &str| {
+        let rest = &l[l.find(MARKER)? + MARKER.len()..];
+        rest[..rest.find(',')?].trim().parse::<usize>().ok()
+    };
+    let mut lines = registry.lines().map(String::from).collect::<Vec<String>>();
+    let registered = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, l)| Some((i, registration_day(l)?)))
+        .collect::<Vec<(usize, usize)>>();
+    if registered.iter().any(|&(_, d)| d == day) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("Day {day} is already registered"),
+        ));
+    }
+    let &(last, _) = registered
+        .last()
+        .ok_or_else(|| Error::new(ErrorKind::NotFound, "No registrations found in registry.rs"))?;
+    let position = registered
+        .iter()
+        .find(|&&(_, d)| d > day)
+        .map_or(last + 1, |&(i, _)| i);
+    // Keep a following registration's feature gate above it
+    let position = match position.checked_sub(1) {
+        Some(above) if lines[above].trim_start().starts_with("#[cfg(") => above,
+        _ => position,
+    };
+    let indent = lines[last][..lines[last].find(MARKER).unwrap_or(0)].to_string();
+    lines.splice(
+        position..position,
+        [
+            format!("{indent}{}", feature_gate(day)),
+            format!("{indent}registry.register::<{day}, crate::day{day}::Solution>();"),
+        ],
+    );
+    Ok(lines.join("\n") + "\n")
+}
+
+/// Add a `dayN` feature to Cargo.toml, enabled by `all`
+fn add_feature(manifest: &str, day: usize) -> Result<String> {
+    let feature = format!("day{day} = []");
+    let mut lines = manifest.lines().map(String::from).collect::<Vec<String>>();
+    if lines.contains(&feature) {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("Cargo.toml already has a day{day} feature"),
+        ));
+    }
+    let missing = || Error::new(ErrorKind::NotFound, "No `all` feature found in Cargo.toml");
+    let all = lines
+        .iter()
+        .position(|l| l == "all = [")
+        .ok_or_else(missing)?;
+    let all_end = all
+        + lines[all..]
+            .iter()
+            .position(|l| l == "]")
+            .ok_or_else(missing)?;
+    lines.insert(all_end, format!("    \"day{day}\","));
+    let is_day_feature = |l: &String| l.starts_with("day") && l.ends_with(" = []");
+    let last = lines
+        .iter()
+        .rposition(is_day_feature)
+        .unwrap_or(all_end + 1);
+    lines.insert(last + 1, feature);
+    Ok(lines.join("\n") + "\n")
+}
+
+fn create(path: &Path, contents: &str, created: &mut Vec<PathBuf>) -> Result<()> {
+    if !path.exists() {
+        fs::write(path, contents)?;
+        created.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+/// Create `src/dayN.rs` from the template, its data directory with empty
+/// inputs and a sidecar awaiting the example's answers, its feature, its
+/// module declaration and its registration. Returns every file created or
+/// modified. Until the answers are filled in, the example shows as missing
+/// rather than passing.
+pub fn new_day(root: &Path, day: usize) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&day) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("Advent of Code has no day {day}"),
+        ));
+    }
+    let src = root.join("src");
+    let source_path = src.join(format!("day{day}.rs"));
+    if source_path.exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", source_path.display()),
+        ));
+    }
+
+    // Check every edit applies before writing anything
+    let template = fs::read_to_string(src.join("template.txt"))?;
+    let lib_path = src.join("lib.rs");
+    let lib = add_module(&fs::read_to_string(&lib_path)?, day)?;
+    let registry_path = src.join("registry.rs");
+    let registry = add_registration(&fs::read_to_string(&registry_path)?, day)?;
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = add_feature(&fs::read_to_string(&manifest_path)?, day)?;
+
+    fs::write(&source_path, render_template(&template, day))?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+    fs::write(&manifest_path, manifest)?;
+    let mut changed = vec![source_path, lib_path, registry_path, manifest_path];
+
+    let data = root.join("data").join(day.to_string());
+    fs::create_dir_all(&data)?;
+    create(&data.join("test1.txt"), "", &mut changed)?;
+    create(
+        &data.join("test1.answers.toml"),
+        "# The example's answers, once known, as in\n# part1 = 24000\n# part2 = \"CMZ\"\n",
+        &mut changed,
+    )?;
+    create(&data.join("input.txt"), "", &mut changed)?;
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_renders_the_day_number() -> Result<()> {
+        let template = fs::read_to_string(project_root().join("src/template.txt"))?;
+        let rendered = render_template(&template, 16);
+        assert!(rendered.contains("impl Solvable<16> for Solution"));
+        assert!(!rendered.contains("{{day}}"));
+        Ok(())
+    }
+
+    #[test]
+    fn it_adds_the_module_in_rustfmt_order() -> Result<()> {
+        let lib = "#[cfg(feature = \"day1\")]\npub mod day1;\n#[cfg(feature = \"day10\")]\n\
+                   pub mod day10;\npub mod day2;\n\npub mod solvable;\n";
+        assert_eq!(
+            add_module(lib, 16)?,
+            "#[cfg(feature = \"day1\")]\npub mod day1;\n#[cfg(feature = \"day10\")]\npub mod day10;\n\
+             #[cfg(feature = \"day16\")]\npub mod day16;\npub mod day2;\n\npub mod solvable;\n"
+        );
+        assert!(add_module(lib, 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_registers_the_day_in_order() -> Result<()> {
+        let registry = "        #[cfg(feature = \"day1\")]\n        \
+                        registry.register::<1, crate::day1::Solution>();\n        \
+                        #[cfg(feature = \"day3\")]\n        \
+                        registry.register::<3, crate::day3::Solution>();\n        registry\n";
+        let updated = add_registration(registry, 2)?;
+        assert_eq!(
+            updated.lines().skip(2).take(3).collect::<Vec<&str>>(),
+            [
+                "        #[cfg(feature = \"day2\")]",
+                "        registry.register::<2, crate::day2::Solution>();",
+                "        #[cfg(feature = \"day3\")]",
+            ]
+        );
+        assert!(add_registration(registry, 3).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_adds_a_feature_enabled_by_all() -> Result<()> {
+        let manifest = "[features]\ndefault = [\"all\"]\nall = [\n    \"day1\",\n]\nday1 = []\n\n\
+                        [dependencies]\n";
+        assert_eq!(
+            add_feature(manifest, 2)?,
+            "[features]\ndefault = [\"all\"]\nall = [\n    \"day1\",\n    \"day2\",\n]\n\
+             day1 = []\nday2 = []\n\n[dependencies]\n"
+        );
+        assert!(add_feature(manifest, 1).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn it_creates_a_new_day_in_a_source_tree() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src"))?;
+        for file in ["template.txt", "lib.rs", "registry.rs"] {
+            fs::copy(
+                project_root().join("src").join(file),
+                root.join("src").join(file),
+            )?;
+        }
+        fs::copy(project_root().join("Cargo.toml"), root.join("Cargo.toml"))?;
+        // A day the tree does not have yet
+        let lib = fs::read_to_string(root.join("src/lib.rs"))?;
+        let day = (1..=25)
+            .rev()
+            .find(|d| !lib.contains(&format!("pub mod day{d};")))
+            .expect("every day already exists");
+
+        let changed = new_day(&root, day)?;
+        assert_eq!(changed.len(), 7);
+        assert!(fs::read_to_string(root.join(format!("src/day{day}.rs")))?
+            .contains(&format!("Solvable<{day}>")));
+        assert!(
+            fs::read_to_string(root.join("src/lib.rs"))?.contains(&format!(
+                "#[cfg(feature = \"day{day}\")]\npub mod day{day};"
+            ))
+        );
+        assert!(
+            fs::read_to_string(root.join("Cargo.toml"))?.contains(&format!("\nday{day} = []\n"))
+        );
+        assert!(
+            fs::read_to_string(root.join("src/registry.rs"))?.contains(&format!(
+                "registry.register::<{day}, crate::day{day}::Solution>();"
+            ))
+        );
+        let data = root.join("data").join(day.to_string());
+        assert!(data.join("test1.txt").exists());
+        let sidecar = fs::read_to_string(data.join("test1.answers.toml"))?;
+        assert!(sidecar.parse::<toml::Table>().is_ok_and(|t| t.is_empty()));
+        assert!(data.join("input.txt").exists());
+        assert!(new_day(&root, day).is_err());
+
+        fs::remove_dir_all(&root)
+    }
+}
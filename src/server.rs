@@ -0,0 +1,242 @@
+//! A small HTTP front end to the registry, for tools that want answers
+//! without running `aoc`. Puzzle text is posted to `/day/{n}/part/{p}`,
+//! with any parameters in the query string, and the reply is the same JSON
+//! record as `aoc run --format json`:
+//!
+//! ```text
+//! $ curl --data-binary @data/15/test1.txt 'localhost:8022/day/15/part/1?row=10'
+//! {"day":15,"part":1,"input":"-","answer":{"type":"integer","value":26},...}
+//! ```
+
+use crate::error::AocError;
+use crate::params::{parse_override, Override};
+use crate::registry::Registry;
+use crate::report::{solve_part, Outcome, Record};
+use crate::solvable::Source;
+use log::warn;
+use serde_json::json;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+/// Largest puzzle text accepted, well above any real input
+pub const MAX_BODY: usize = 1 << 20;
+
+/// How long a client may stall while sending a request or reading the reply
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+impl Response {
+    fn error(status: u16, reason: impl Into<String>) -> Self {
+        Response {
+            status,
+            body: json!({ "error": reason.into() }).to_string(),
+        }
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
+
+/// The day and part of a `/day/{n}/part/{p}` path, and the overrides in its
+/// query string
+fn route(target: &str) -> Result<(usize, u8, Vec<Override>), Response> {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let not_found = || Response::error(404, format!("No such endpoint {path}"));
+    let segments = path.trim_matches('/').split('/').collect::<Vec<&str>>();
+    let ["day", day, "part", part] = segments[..] else {
+        return Err(not_found());
+    };
+    let day = day.parse::<usize>().map_err(|_| not_found())?;
+    let part = part
+        .parse::<u8>()
+        .ok()
+        .filter(|p| (1..=2).contains(p))
+        .ok_or_else(not_found)?;
+    let overrides = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| parse_override(pair).map_err(|reason| Response::error(400, reason)))
+        .collect::<Result<_, _>>()?;
+    Ok((day, part, overrides))
+}
+
+/// Answer a single request. Only `POST /day/{n}/part/{p}` is served, for
+/// the days `registry` has.
+pub fn handle(registry: &Registry, method: &str, target: &str, body: &str) -> Response {
+    let (day, part, overrides) = match route(target) {
+        Ok(route) => route,
+        Err(response) => return response,
+    };
+    if method != "POST" {
+        return Response::error(405, "Post the puzzle text");
+    }
+    let Some(solver) = registry.solver_with(day, Source::Text(body.to_string()), &overrides) else {
+        return Response::error(404, format!("Day {day} has no solution"));
+    };
+    let result = solve_part(solver.as_ref(), part);
+    let status = match &result.outcome {
+        Outcome::Answer(_) => 200,
+        Outcome::Error(AocError::Parse { .. } | AocError::Param { .. }) => 400,
+        Outcome::Error(_) => 422,
+        Outcome::Panic(_) => 500,
+    };
+    match serde_json::to_string(&Record::new("-", &result)) {
+        Ok(body) => Response { status, body },
+        Err(e) => Response::error(500, e.to_string()),
+    }
+}
+
+/// Read one request from `stream` and write back the response
+fn respond(registry: &Registry, stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut words = request_line.split_whitespace();
+    let (method, target) = (words.next().unwrap_or(""), words.next().unwrap_or(""));
+
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let response = if length > MAX_BODY {
+        Response::error(413, format!("Inputs are limited to {MAX_BODY} bytes"))
+    } else {
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        match String::from_utf8(body) {
+            Ok(body) => handle(registry, method, target, &body),
+            Err(_) => Response::error(400, "The puzzle text is not UTF-8"),
+        }
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Connection: close\r\n\r\n{}",
+        response.status,
+        reason_phrase(response.status),
+        response.body.len(),
+        response.body
+    )
+}
+
+/// Listen on `port` of localhost only; 0 picks a free port
+pub fn bind(port: u16) -> io::Result<TcpListener> {
+    TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+}
+
+/// Serve requests from `listener` forever, each on its own thread. A
+/// connection that fails to be accepted is logged and skipped.
+pub fn serve(listener: &TcpListener, registry: &Registry) {
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    warn!("Failed to accept a connection: {e}");
+                    continue;
+                }
+            };
+            scope.spawn(move || {
+                if let Err(e) = respond(registry, stream) {
+                    warn!("Dropped a request: {e}");
+                }
+            });
+        }
+    })
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+mod test {
+    use super::*;
+    use std::error::Error;
+    use std::fs;
+
+    #[test]
+    fn it_routes_only_day_and_part() {
+        let registry = Registry::new();
+        assert_eq!(handle(&registry, "POST", "/", "").status, 404);
+        assert_eq!(handle(&registry, "POST", "/day/1/part/3", "").status, 404);
+        assert_eq!(handle(&registry, "GET", "/day/1/part/1", "").status, 405);
+        let unknown = handle(&registry, "POST", "/day/1/part/1", "");
+        assert_eq!(unknown.status, 404);
+        assert!(unknown.body.contains("Day 1 has no solution"));
+        assert_eq!(
+            handle(&registry, "POST", "/day/1/part/1?row", "").status,
+            400
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day15"))]
+    fn it_answers_with_json() -> Result<(), Box<dyn Error>> {
+        let registry = Registry::default();
+        let response = handle(&registry, "POST", "/day/1/part/2", "1\n\n2\n\n3\n\n4\n");
+        assert_eq!(response.status, 200);
+        let json = serde_json::from_str::<serde_json::Value>(&response.body)?;
+        assert_eq!(json["answer"]["value"], 9);
+        assert!(json["duration_ns"].is_u64());
+
+        let sensors = fs::read_to_string(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("data/15/test1.txt"),
+        )?;
+        let response = handle(&registry, "POST", "/day/15/part/1?row=10", &sensors);
+        assert_eq!(response.status, 200, "{}", response.body);
+        assert!(response.body.contains("\"value\":26"));
+
+        let response = handle(&registry, "POST", "/day/1/part/1", "1\nlots\n");
+        assert_eq!(response.status, 400);
+        assert!(
+            response.body.contains("line 2, column 1"),
+            "{}",
+            response.body
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn it_serves_over_http() -> Result<(), Box<dyn Error>> {
+        let listener = bind(0)?;
+        let url = format!("http://{}/day/1/part/1", listener.local_addr()?);
+        thread::spawn(move || serve(&listener, &Registry::default()));
+
+        let response = ureq::post(&url).send_string("100\n200\n\n250\n")?;
+        assert_eq!(response.content_type(), "application/json");
+        let json = serde_json::from_str::<serde_json::Value>(&response.into_string()?)?;
+        assert_eq!(json["answer"]["value"], 300);
+
+        match ureq::get(&url).call() {
+            Err(ureq::Error::Status(405, _)) => Ok(()),
+            other => Err(format!("Expected a 405, got {other:?}").into()),
+        }
+    }
+}
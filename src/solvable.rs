@@ -1,12 +1,231 @@
-use std::error::Error;
-use std::path::PathBuf;
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::params::{Param, Params};
+use std::borrow::Cow;
+use std::env;
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::fs;
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Environment variable overriding the directory that holds `N/` for each day
+pub const DATA_DIR_VAR: &str = "AOC_DATA_DIR";
+
+static DATA_ROOT: OnceLock<PathBuf> = OnceLock::new();
+
+/// Pick the data root from an explicit setting, then the environment, then
+/// the `data` directory of this crate.
+pub fn resolve_data_root(explicit: Option<PathBuf>, from_env: Option<OsString>) -> PathBuf {
+    explicit
+        .or_else(|| from_env.filter(|v| !v.is_empty()).map(PathBuf::from))
+        .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("data"))
+}
+
+/// Use `root` as the data root for the rest of the process. Has no effect
+/// once the data root has been read.
+pub fn set_data_root(root: PathBuf) {
+    let _ = DATA_ROOT.set(root);
+}
+
+pub fn data_root() -> &'static Path {
+    DATA_ROOT.get_or_init(|| resolve_data_root(None, env::var_os(DATA_DIR_VAR)))
+}
+
+/// Directory holding the input files and expected answers for `day`
+pub fn data_dir(day: usize) -> PathBuf {
+    data_root().join(day.to_string())
+}
+
+/// Where a solution reads its puzzle input from
+#[derive(Clone, Debug)]
+pub enum Source {
+    File(PathBuf),
+    Text(String),
+}
+
+impl Source {
+    /// The input `filename` of `day`: a file in that day's data directory,
+    /// or any file when given an absolute path.
+    pub fn file(day: usize, filename: &str) -> Self {
+        Source::File(data_dir(day).join(filename))
+    }
+
+    pub fn read(&self, day: usize) -> Result<Cow<'_, str>, AocError> {
+        match self {
+            Source::File(path) => {
+                fs::read_to_string(path)
+                    .map(Cow::Owned)
+                    .map_err(|source| AocError::Io {
+                        day,
+                        path: path.clone(),
+                        source,
+                    })
+            }
+            Source::Text(text) => Ok(Cow::Borrowed(text)),
+        }
+    }
+
+    /// Tag `error` with `day` and, for files, the file's path
+    pub fn context(&self, day: usize, error: AocError) -> AocError {
+        match self {
+            Source::File(path) => error.with_context(day, path),
+            Source::Text(_) => error.with_day(day),
+        }
+    }
+}
+
+/// A part's working, advanced a step at a time so it can be watched from
+/// `aoc repl`. Its rendering is the current state.
+pub trait Simulation: Display {
+    /// Advance one step, returning false once there is nothing left to do
+    fn step(&mut self) -> Result<bool, AocError>;
+}
+
+/// A day's puzzle. The input is parsed once into `Input`, which both parts
+/// then share.
+pub trait Solvable<const N: usize>: Sized {
+    type Input;
+
+    fn from_source(source: Source) -> Self;
+    fn source(&self) -> &Source;
+
+    fn new(filename: &str) -> Self {
+        Self::from_source(Source::file(N, filename))
+    }
+
+    /// Solve puzzle text that is already in memory
+    fn from_text(text: &str) -> Self {
+        Self::from_source(Source::Text(text.to_string()))
+    }
+
+    /// Read the whole puzzle from `reader`, such as locked stdin
+    fn from_reader<R: BufRead>(mut reader: R) -> Result<Self, AocError> {
+        let mut text = String::new();
+        reader
+            .read_to_string(&mut text)
+            .map_err(|source| AocError::Io {
+                day: N,
+                path: PathBuf::from("<reader>"),
+                source,
+            })?;
+        Ok(Self::from_source(Source::Text(text)))
+    }
 
-pub trait Solvable<const N: usize> {
-    fn new(filename: &str) -> Self;
     fn data_path() -> PathBuf {
-        format!("data/{}", N).into()
+        data_dir(N)
+    }
+
+    /// Named settings this puzzle reads, with their defaults
+    const PARAMS: &'static [Param] = &[];
+
+    fn parse(text: &str) -> Result<Self::Input, AocError>;
+    fn part1(&self, input: &Self::Input, params: &Params) -> Result<Answer, AocError>;
+    fn part2(&self, input: &Self::Input, params: &Params) -> Result<Answer, AocError>;
+
+    /// Solving `part` as a simulation to step through, for days whose
+    /// answers come from some evolving state
+    fn simulation<'a>(
+        &self,
+        _input: &'a Self::Input,
+        _params: &'a Params,
+        _part: u8,
+    ) -> Result<Option<Box<dyn Simulation + 'a>>, AocError> {
+        Ok(None)
+    }
+
+    /// Read and parse this puzzle's input
+    fn input(&self) -> Result<Self::Input, AocError> {
+        let source = self.source();
+        Self::parse(&source.read(N)?).map_err(|e| source.context(N, e))
+    }
+
+    /// The parameters for this puzzle's input: the defaults, updated by the
+    /// input's config file
+    fn params(&self) -> Result<Params, AocError> {
+        Params::resolve(N, Self::PARAMS, self.source(), &[]).map_err(|e| e.with_day(N))
+    }
+
+    /// Read, parse and solve part 1. The input is parsed again on every
+    /// call; a solver from the [`Registry`](crate::registry::Registry)
+    /// parses it once for both parts.
+    fn answer1(&self) -> Result<Answer, AocError> {
+        self.part1(&self.input()?, &self.params()?)
+            .map_err(|e| self.source().context(N, e))
     }
 
-    fn answer1(&self) -> Result<String, Box<dyn Error>>;
-    fn answer2(&self) -> Result<String, Box<dyn Error>>;
+    /// Read, parse and solve part 2
+    fn answer2(&self) -> Result<Answer, AocError> {
+        self.part2(&self.input()?, &self.params()?)
+            .map_err(|e| self.source().context(N, e))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_prefers_an_explicit_data_root() {
+        let explicit = Some(PathBuf::from("/srv/aoc"));
+        let from_env = Some(OsString::from("/tmp/aoc"));
+        assert_eq!(
+            resolve_data_root(explicit, from_env.clone()),
+            Path::new("/srv/aoc")
+        );
+        assert_eq!(resolve_data_root(None, from_env), Path::new("/tmp/aoc"));
+        assert!(resolve_data_root(None, Some(OsString::new())).is_absolute());
+        assert!(resolve_data_root(None, None).ends_with("data"));
+    }
+
+    /// Counts the lines of its input
+    struct LineCount(Source);
+
+    impl Solvable<1> for LineCount {
+        type Input = usize;
+
+        fn from_source(source: Source) -> Self {
+            LineCount(source)
+        }
+        fn source(&self) -> &Source {
+            &self.0
+        }
+        fn parse(text: &str) -> Result<usize, AocError> {
+            Ok(text.lines().count())
+        }
+        fn part1(&self, lines: &usize, _: &Params) -> Result<Answer, AocError> {
+            Ok((*lines).into())
+        }
+        fn part2(&self, _: &usize, _: &Params) -> Result<Answer, AocError> {
+            Err(AocError::parse(2, 3, "bad"))
+        }
+    }
+
+    #[test]
+    fn it_accepts_absolute_input_paths() {
+        let absolute = data_dir(1).join("test1.txt");
+        match Source::file(1, absolute.to_str().unwrap()) {
+            Source::File(path) => assert_eq!(path, absolute),
+            Source::Text(_) => panic!("Expected a file"),
+        }
+        match Source::file(1, "test1.txt") {
+            Source::File(path) => assert_eq!(path, absolute),
+            Source::Text(_) => panic!("Expected a file"),
+        }
+    }
+
+    #[test]
+    fn it_solves_text_and_readers() -> Result<(), AocError> {
+        assert_eq!(LineCount::from_text("a\nb\nc\n").answer1()?, 3);
+        assert_eq!(LineCount::from_reader("a\nb\n".as_bytes())?.answer1()?, 2);
+        match LineCount::from_text("a\nb\n").answer2() {
+            Err(AocError::Parse { day, file, .. }) => {
+                assert_eq!(day, Some(1));
+                assert_eq!(file, None);
+            }
+            _ => panic!("Expected a parse error"),
+        }
+        Ok(())
+    }
 }
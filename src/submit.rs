@@ -0,0 +1,361 @@
+use crate::answer::Answer;
+use crate::client::Client;
+use crate::solvable::data_root;
+use lazy_regex::regex;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Every answer submitted, one JSON record per line
+pub fn log_path() -> PathBuf {
+    data_root().join("submissions.jsonl")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    /// Answered before the rate limit allowed; the answer was not checked
+    TooSoon,
+    /// The part is locked or already solved
+    WrongLevel,
+    /// A page we could not make sense of
+    Unknown,
+}
+
+/// Which way an incorrect integer answer was off, when the site says
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hint {
+    TooHigh,
+    TooLow,
+}
+
+/// What the site made of a submitted answer
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub verdict: Verdict,
+    pub hint: Option<Hint>,
+    /// Seconds until the site accepts another answer
+    pub wait: Option<u64>,
+    /// The text of the page's article, without markup
+    pub message: String,
+}
+
+/// Read the response page to a submitted answer
+pub fn parse_response(html: &str) -> Response {
+    let article = match (html.find("<article"), html.find("</article>")) {
+        (Some(start), Some(end)) if start < end => &html[start..end],
+        _ => html,
+    };
+    let text = regex!(r"<[^>]*>").replace_all(article, "");
+    let message = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    let verdict = if message.contains("That's the right answer") {
+        Verdict::Correct
+    } else if message.contains("not the right answer") {
+        Verdict::Incorrect
+    } else if message.contains("answer too recently") {
+        Verdict::TooSoon
+    } else if message.contains("solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    };
+    let hint = if message.contains("answer is too high") {
+        Some(Hint::TooHigh)
+    } else if message.contains("answer is too low") {
+        Some(Hint::TooLow)
+    } else {
+        None
+    };
+    let wait = match regex!(r"(?:(\d+)m )?(\d+)s left to wait").captures(&message) {
+        Some(c) => {
+            let number = |i| {
+                c.get(i)
+                    .map_or(0, |m| m.as_str().parse::<u64>().unwrap_or(0))
+            };
+            Some(number(1) * 60 + number(2))
+        }
+        None => regex!(r"wait (one|\d+) minutes? before")
+            .captures(&message)
+            .map(|c| c[1].parse::<u64>().unwrap_or(1) * 60),
+    };
+    Response {
+        verdict,
+        hint,
+        wait,
+        message,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub day: usize,
+    pub part: u8,
+    pub answer: String,
+    pub verdict: Verdict,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hint: Option<Hint>,
+    /// When the answer was submitted, in seconds since the Unix epoch
+    pub at: u64,
+    /// When the site will accept another answer, in seconds since the Unix
+    /// epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_at: Option<u64>,
+    pub message: String,
+}
+
+/// Why an answer was not submitted
+#[derive(Debug, PartialEq)]
+pub enum Refusal {
+    /// The part was already solved, with this answer
+    Solved(String),
+    /// The site already rejected this answer
+    KnownWrong(String),
+    /// The site said this earlier answer was too high or too low, which
+    /// rules the new one out too
+    OutOfRange(String, Hint),
+    /// Seconds left until the site accepts another answer
+    Wait(u64),
+}
+
+impl Display for Refusal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Refusal::Solved(answer) => write!(f, "Already solved with {answer}"),
+            Refusal::KnownWrong(answer) => write!(f, "{answer} was already rejected"),
+            Refusal::OutOfRange(bound, Hint::TooHigh) => {
+                write!(f, "Answers of {bound} or more are too high")
+            }
+            Refusal::OutOfRange(bound, Hint::TooLow) => {
+                write!(f, "Answers of {bound} or less are too low")
+            }
+            Refusal::Wait(seconds) => {
+                write!(f, "Answering too soon; wait another {seconds}s")
+            }
+        }
+    }
+}
+
+impl Error for Refusal {}
+
+pub struct SubmissionLog {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl SubmissionLog {
+    /// Read the log at `path`, which need not exist yet
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("{}: {e}", path.display()).into()),
+        };
+        let attempts = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| format!("{}:{}: {e}", path.display(), i + 1))
+            })
+            .collect::<Result<Vec<Attempt>, String>>()?;
+        Ok(SubmissionLog {
+            path: path.to_path_buf(),
+            attempts,
+        })
+    }
+
+    pub fn attempts(&self) -> &[Attempt] {
+        &self.attempts
+    }
+
+    /// Whether `answer` to `part` of `day` is worth submitting at `now`
+    pub fn check(&self, day: usize, part: u8, answer: &str, now: u64) -> Result<(), Refusal> {
+        for attempt in self
+            .attempts
+            .iter()
+            .filter(|a| a.day == day && a.part == part)
+        {
+            match attempt.verdict {
+                Verdict::Correct => return Err(Refusal::Solved(attempt.answer.clone())),
+                Verdict::Incorrect if attempt.answer == answer => {
+                    return Err(Refusal::KnownWrong(attempt.answer.clone()))
+                }
+                Verdict::Incorrect => {
+                    let (Some(hint), Ok(bound), Ok(n)) = (
+                        attempt.hint,
+                        attempt.answer.parse::<i128>(),
+                        answer.parse::<i128>(),
+                    ) else {
+                        continue;
+                    };
+                    let excluded = match hint {
+                        Hint::TooHigh => n >= bound,
+                        Hint::TooLow => n <= bound,
+                    };
+                    if excluded {
+                        return Err(Refusal::OutOfRange(attempt.answer.clone(), hint));
+                    }
+                }
+                _ => {}
+            }
+        }
+        match self.attempts.iter().filter_map(|a| a.retry_at).max() {
+            Some(retry_at) if retry_at > now => Err(Refusal::Wait(retry_at - now)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Add `attempt` to the end of the log
+    pub fn record(&mut self, attempt: Attempt) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&attempt)?)?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Submit `answer` to `part` of `day`, unless `log` shows the site would
+/// not accept it, and record what the site made of it
+pub fn submit(
+    client: &Client,
+    log: &mut SubmissionLog,
+    day: usize,
+    part: u8,
+    answer: &Answer,
+) -> Result<Attempt, Box<dyn Error>> {
+    if let Answer::Grid(_) = answer {
+        return Err("Read the letters off the grid and submit them with --answer".into());
+    }
+    let answer = answer.to_string();
+    let now = unix_now();
+    log.check(day, part, &answer, now)?;
+    let response = parse_response(&client.answer(day, part, &answer)?);
+    let attempt = Attempt {
+        day,
+        part,
+        answer,
+        verdict: response.verdict,
+        hint: response.hint,
+        at: now,
+        retry_at: response.wait.map(|wait| now + wait),
+        message: response.message,
+    };
+    log.record(attempt.clone())?;
+    Ok(attempt)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::client::test::stub;
+    use std::env;
+
+    const CORRECT: &str = "<main>\n<article><p>That's the right answer!  You are <em>one gold star</em> \
+        closer to collecting enough star fruit. <a href=\"/2022/day/1\">[Continue to Part Two]</a></p></article>\n</main>";
+    const TOO_HIGH: &str = "<main>\n<article><p>That's not the right answer; your answer is too high.  \
+        If you're stuck, make sure you're using the full input data.  Please wait one minute before trying \
+        again. <a href=\"/2022/day/1\">[Return to Day 1]</a></p></article>\n</main>";
+    const TOO_SOON: &str =
+        "<main>\n<article><p>You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 38s left to wait. \
+        <a href=\"/2022/day/1\">[Return to Day 1]</a></p></article>\n</main>";
+
+    fn scratch_log(name: &str) -> SubmissionLog {
+        let path = env::temp_dir().join(format!("aoc-submit-{name}-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        SubmissionLog::load(&path).unwrap()
+    }
+
+    #[test]
+    fn it_parses_the_response_pages() {
+        let correct = parse_response(CORRECT);
+        assert_eq!(correct.verdict, Verdict::Correct);
+        assert!(correct
+            .message
+            .starts_with("That's the right answer! You are one gold star"));
+
+        let too_high = parse_response(TOO_HIGH);
+        assert_eq!(
+            (too_high.verdict, too_high.hint, too_high.wait),
+            (Verdict::Incorrect, Some(Hint::TooHigh), Some(60))
+        );
+
+        let too_soon = parse_response(TOO_SOON);
+        assert_eq!(
+            (too_soon.verdict, too_soon.wait),
+            (Verdict::TooSoon, Some(98))
+        );
+
+        let page = "<article><p>You don't seem to be solving the right level.</p></article>";
+        assert_eq!(parse_response(page).verdict, Verdict::WrongLevel);
+        assert_eq!(parse_response("<html></html>").verdict, Verdict::Unknown);
+    }
+
+    #[test]
+    fn it_logs_attempts_and_never_resubmits_a_wrong_answer() -> Result<(), Box<dyn Error>> {
+        let (base_url, server) = stub(vec![(200, TOO_HIGH)]);
+        let client = Client::new(base_url, Some("abc".to_string()));
+        let mut log = scratch_log("wrong");
+
+        let attempt = submit(&client, &mut log, 1, 2, &Answer::Integer(500))?;
+        assert_eq!(attempt.verdict, Verdict::Incorrect);
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2022/day/1/answer "));
+        assert!(requests[0].ends_with("level=2&answer=500"));
+
+        // The stub has stopped serving, so these must be refused unsent
+        let later = attempt.at + 3600;
+        assert_eq!(
+            log.check(1, 2, "500", later),
+            Err(Refusal::KnownWrong("500".into()))
+        );
+        assert_eq!(
+            log.check(1, 2, "501", later),
+            Err(Refusal::OutOfRange("500".into(), Hint::TooHigh))
+        );
+        assert_eq!(log.check(1, 2, "499", later), Ok(()));
+        assert_eq!(log.check(1, 2, "499", attempt.at), Err(Refusal::Wait(60)));
+        let error = submit(&client, &mut log, 1, 2, &Answer::Integer(500)).unwrap_err();
+        assert!(error.to_string().contains("already rejected"), "{error}");
+
+        let reloaded = SubmissionLog::load(&log.path)?;
+        assert_eq!(reloaded.attempts(), &[attempt]);
+        Ok(())
+    }
+
+    #[test]
+    fn it_stops_submitting_once_solved() -> Result<(), Box<dyn Error>> {
+        let (base_url, server) = stub(vec![(200, CORRECT)]);
+        let client = Client::new(base_url, Some("abc".to_string()));
+        let mut log = scratch_log("solved");
+
+        submit(&client, &mut log, 1, 1, &Answer::Text("CMZ".into()))?;
+        server.join().unwrap();
+        assert_eq!(
+            log.check(1, 1, "MCD", u64::MAX),
+            Err(Refusal::Solved("CMZ".into()))
+        );
+        assert_eq!(log.check(1, 2, "MCD", u64::MAX), Ok(()));
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use crate::answer::Answer;
+use crate::error::AocError;
+use crate::manifest::{input_files, Manifest};
+use crate::registry::{Registry, Solver};
+use std::collections::BTreeSet;
+
+pub enum Status {
+    Pass,
+    Fail {
+        expected: Answer,
+    },
+    /// The manifest has no expected answer for this file and part
+    Missing,
+    Error(AocError),
+}
+
+pub struct Check {
+    pub day: usize,
+    pub file: String,
+    pub part: u8,
+    pub answer: Option<Answer>,
+    pub status: Status,
+}
+
+/// Compare the answer `solver` gives for `part` of `file` against `manifest`
+pub fn check(solver: &dyn Solver, manifest: &Manifest, file: &str, part: u8) -> Check {
+    let day = solver.day();
+    let result = solver.answer(part);
+    let (answer, status) = match (result, manifest.expected(file, part)) {
+        (Err(e), _) => (None, Status::Error(e)),
+        (Ok(answer), None) => (Some(answer), Status::Missing),
+        (Ok(answer), Some(expected)) if &answer == expected => (Some(answer), Status::Pass),
+        (Ok(answer), Some(expected)) => (
+            Some(answer),
+            Status::Fail {
+                expected: expected.clone(),
+            },
+        ),
+    };
+    Check {
+        day,
+        file: file.to_string(),
+        part,
+        answer,
+        status,
+    }
+}
+
+/// Run both parts of `day` against every input in its data directory and
+/// every file named in its manifest.
+pub fn verify_day(registry: &Registry, day: usize) -> Result<Vec<Check>, AocError> {
+    let manifest = Manifest::load(day)?;
+    let mut files = input_files(day)?.into_iter().collect::<BTreeSet<String>>();
+    files.extend(manifest.files().map(String::from));
+    let mut checks = vec![];
+    for file in files {
+        // Both parts share one solver, so the file is only parsed once
+        let solver = registry
+            .solver(day, &file)
+            .ok_or_else(|| AocError::unsolvable("Day is not registered").with_day(day))?;
+        for part in [1, 2] {
+            checks.push(check(solver.as_ref(), &manifest, &file, part));
+        }
+    }
+    Ok(checks)
+}
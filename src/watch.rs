@@ -0,0 +1,107 @@
+use crate::error::AocError;
+use crate::manifest::{input_files, Manifest};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Notices files being created, modified or removed, by comparing
+/// modification times each time it is polled
+pub struct Watcher {
+    dirs: Vec<PathBuf>,
+    files: Vec<PathBuf>,
+    seen: BTreeMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// Watch every file directly inside `dirs`, and each of `files`
+    pub fn new(dirs: Vec<PathBuf>, files: Vec<PathBuf>) -> Self {
+        let mut watcher = Watcher {
+            dirs,
+            files,
+            seen: BTreeMap::new(),
+        };
+        watcher.seen = watcher.scan();
+        watcher
+    }
+
+    fn scan(&self) -> BTreeMap<PathBuf, SystemTime> {
+        let in_dirs = self
+            .dirs
+            .iter()
+            .filter_map(|dir| fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path());
+        in_dirs
+            .chain(self.files.iter().cloned())
+            .filter_map(|path| {
+                let metadata = fs::metadata(&path).ok().filter(|m| m.is_file())?;
+                Some((path, metadata.modified().ok()?))
+            })
+            .collect()
+    }
+
+    /// The files that changed since the last poll, in path order
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let current = self.scan();
+        let mut changed = current
+            .iter()
+            .filter(|&(path, modified)| self.seen.get(path) != Some(modified))
+            .map(|(path, _)| path)
+            .chain(self.seen.keys().filter(|path| !current.contains_key(*path)))
+            .cloned()
+            .collect::<Vec<PathBuf>>();
+        changed.sort();
+        self.seen = current;
+        changed
+    }
+}
+
+/// The inputs of `day` that are quick enough to re-run on every change:
+/// all of them except those their sidecar marks slow
+pub fn quick_inputs(day: usize) -> Result<Vec<String>, AocError> {
+    let manifest = Manifest::load(day)?;
+    let mut files = input_files(day)?;
+    files.retain(|file| !manifest.get(file).is_some_and(|expected| expected.slow));
+    Ok(files)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+    use std::fs::File;
+    use std::time::Duration;
+
+    #[test]
+    fn it_notices_created_modified_and_removed_files() -> Result<(), std::io::Error> {
+        let dir = env::temp_dir().join(format!("aoc-watch-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir)?;
+        let (old, new) = (dir.join("old.txt"), dir.join("new.txt"));
+        fs::write(&old, "1")?;
+        let mut watcher = Watcher::new(vec![dir.clone()], vec![]);
+        assert!(watcher.poll().is_empty());
+
+        fs::write(&new, "2")?;
+        assert_eq!(watcher.poll(), std::slice::from_ref(&new));
+
+        let modified = fs::metadata(&old)?.modified()? + Duration::from_secs(1);
+        File::options()
+            .write(true)
+            .open(&old)?
+            .set_modified(modified)?;
+        fs::remove_file(&new)?;
+        assert_eq!(watcher.poll(), [new, old]);
+        assert!(watcher.poll().is_empty());
+        fs::remove_dir_all(&dir)
+    }
+
+    #[test]
+    fn it_leaves_out_slow_inputs() -> Result<(), AocError> {
+        assert_eq!(quick_inputs(15)?, ["test1.txt"]);
+        assert!(quick_inputs(1)?.contains(&"input.txt".to_string()));
+        Ok(())
+    }
+}
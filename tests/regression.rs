@@ -0,0 +1,32 @@
+//! One test for each part of each input file under `data/N`, checked
+//! against the expected answers in its sidecar. Adding an example is a
+//! matter of dropping `testN.txt` and `testN.answers.toml` into the day's
+//! data directory.
+
+#![cfg(regression_cases)]
+
+use adventofcode2022::manifest::Manifest;
+use adventofcode2022::registry::Registry;
+use adventofcode2022::solvable;
+use adventofcode2022::verify::{self, Status};
+use std::path::Path;
+
+fn check(day: usize, file: &str, part: u8) {
+    solvable::set_data_root(Path::new(env!("CARGO_MANIFEST_DIR")).join("data"));
+    let manifest = Manifest::load(day).unwrap_or_else(|e| panic!("{e}"));
+    let solver = Registry::default()
+        .solver(day, file)
+        .unwrap_or_else(|| panic!("Day {day} is not registered"));
+    let check = verify::check(solver.as_ref(), &manifest, file, part);
+    match check.status {
+        Status::Pass => {}
+        Status::Fail { expected } => panic!(
+            "Day {day}, part {part} of {file}: expected {expected}, got {}",
+            check.answer.map_or_else(String::new, |a| a.to_string())
+        ),
+        Status::Missing => panic!("Day {day}: no expected answer for part {part} of {file}"),
+        Status::Error(e) => panic!("{e}"),
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/regression.rs"));